@@ -0,0 +1,96 @@
+use std::collections::HashSet;
+
+use crate::deps;
+use crate::models::{Task, TaskState};
+
+/// Finds inconsistencies that parsing alone lets through: duplicate IDs, broken or cyclic
+/// dependencies, impossible date orderings and Iced tasks that can never thaw.
+/// Returns one message per problem, prefixed with the task ID (e.g. "#3: ...").
+pub fn problems(tasks: &[Task]) -> Vec<String> {
+    let mut problems = Vec::new();
+    let mut seen = HashSet::new();
+    for task in tasks {
+        let id = task.id;
+        let mut report = |message: String| problems.push(format!("#{id}: {message}"));
+        if id == 0 {
+            report("IDs start at 1".to_string());
+        }
+        if !seen.insert(id) {
+            report("ID is used by more than one task".to_string());
+        }
+        if task.title.trim().is_empty() {
+            report("title is empty".to_string());
+        }
+        if task.state == TaskState::Iced && task.thaw_date.is_none() {
+            report("Iced without a thaw date, so it never thaws".to_string());
+        }
+        let dates = [
+            ("completed_at", task.completed_at),
+            ("updated_at", task.updated_at),
+            ("thawed_at", task.thawed_at),
+            ("frozen_at", task.frozen_at),
+        ];
+        for (field, date) in dates {
+            if let Some(date) = date
+                && date < task.created_at
+            {
+                report(format!("{field} {date} is before created_at {}", task.created_at));
+            }
+        }
+        for &dep in &task.depends_on {
+            if dep == id {
+                report("depends on itself".to_string());
+            } else if !tasks.iter().any(|t| t.id == dep) {
+                report(format!("depends on missing task #{dep}"));
+            } else if deps::depends_transitively(tasks, dep, id) {
+                report(format!("dependency on #{dep} is part of a cycle"));
+            }
+        }
+    }
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn task(id: u32, state: TaskState) -> Task {
+        Task {
+            id,
+            title: format!("Task {id}"),
+            state,
+            created_at: NaiveDate::from_ymd_opt(2026, 1, 10).unwrap(),
+            ..Task::default()
+        }
+    }
+
+    #[test]
+    fn consistent_tasks_have_no_problems() {
+        let mut second = task(2, TaskState::Melted);
+        second.depends_on = vec![1];
+        assert!(problems(&[task(1, TaskState::Melting), second]).is_empty());
+    }
+
+    #[test]
+    fn reports_each_problem_with_its_task() {
+        let mut first = task(1, TaskState::Iced);
+        first.depends_on = vec![2, 9];
+        let mut second = task(2, TaskState::Evaporated);
+        second.depends_on = vec![1];
+        second.completed_at = NaiveDate::from_ymd_opt(2026, 1, 1);
+        let duplicate = task(2, TaskState::Melted);
+
+        assert_eq!(
+            problems(&[first, second, duplicate]),
+            vec![
+                "#1: Iced without a thaw date, so it never thaws",
+                "#1: dependency on #2 is part of a cycle",
+                "#1: depends on missing task #9",
+                "#2: completed_at 2026-01-01 is before created_at 2026-01-10",
+                "#2: dependency on #1 is part of a cycle",
+                "#2: ID is used by more than one task",
+            ]
+        );
+    }
+}
@@ -1,4 +1,7 @@
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+
+use crate::config::ThawTarget;
+use crate::models::{Priority, TaskState};
 
 /// Kelvin - A CLI task management tool using thermodynamic metaphors
 #[derive(Parser, Debug)]
@@ -6,83 +9,796 @@ use clap::{Parser, Subcommand};
 pub struct Cli {
     #[command(subcommand)]
     pub command: Commands,
+    /// Skip every confirmation prompt and safety guard
+    #[arg(short, long, global = true, visible_alias = "force", visible_short_alias = 'f')]
+    pub yes: bool,
+    /// Where tasks thaw to in this run, overriding `defaults.thaw_to`
+    #[arg(long, value_enum, global = true, value_name = "STATE")]
+    pub thaw_to: Option<ThawTarget>,
+    /// Work on the list <LIST>.json next to the data file, with its `[lists.<LIST>]` settings
+    #[arg(long = "in", global = true, value_name = "LIST")]
+    pub in_list: Option<String>,
 }
 
 #[derive(Subcommand, Debug)]
 pub enum Commands {
     /// Add a new task
-    Add {
-        /// Task title
-        title: String,
-        /// Task description
-        #[arg(long = "desc")]
-        description: Option<String>,
-        /// Thaw date (e.g., 3d, 1w, 2026-03-01). If specified, the task is created in Iced state.
-        #[arg(short = 'd', long = "date")]
-        thaw_date: Option<String>,
-        /// Due date (e.g., 3d, 1w, 2026-03-01)
-        #[arg(long = "due")]
-        due_date: Option<String>,
-    },
+    Add(AddArgs),
 
     /// Edit an existing task
-    Edit {
-        /// Task ID
-        id: u32,
-        /// New title
-        #[arg(short = 't', long = "title")]
+    Edit(EditArgs),
+
+    /// Create a new Melted task from an existing one (title, description, tags, priority, due date)
+    Clone {
+        /// ID of the task to copy
+        #[arg(value_parser = parse_task_ref)]
+        id: TaskRef,
+        /// Title for the copy (defaults to the original title)
+        #[arg(short, long, value_parser = parse_title)]
         title: Option<String>,
-        /// New description
-        #[arg(long = "desc")]
-        description: Option<String>,
-        /// Change the thaw date (e.g., 3d, 1w, 2026-03-01)
-        #[arg(short = 'd', long = "date")]
-        thaw_date: Option<String>,
-        /// Change the due date (e.g., 3d, 1w, 2026-03-01)
-        #[arg(long = "due")]
-        due_date: Option<String>,
     },
 
-    /// Show task details
-    Show {
+    /// Remove a task from storage for good, after confirming (`--force` skips the prompt)
+    Delete {
         /// Task ID
-        id: u32,
+        #[arg(value_parser = parse_task_ref)]
+        id: TaskRef,
     },
 
-    /// List tasks
-    List {
-        /// Show frozen (Iced) tasks
+    /// Fold one task into another and delete it (see README for how fields are combined)
+    Merge {
+        /// Task to merge and delete
+        #[arg(value_parser = parse_task_ref)]
+        from: TaskRef,
+        /// Task that receives the merged details (in the same list)
+        #[arg(value_parser = parse_task_ref)]
+        into: TaskRef,
+    },
+
+    /// Give a task a different ID, updating the dependencies that point at it
+    #[command(visible_alias = "mv")]
+    SetId {
+        /// Current task ID
+        #[arg(value_parser = parse_task_ref)]
+        old: TaskRef,
+        /// New, unused task ID
+        new: u32,
+    },
+
+    /// Push a task's due date back (by a week unless --by says otherwise)
+    #[command(visible_alias = "bump-due")]
+    Defer {
+        /// Task ID
+        #[arg(value_parser = parse_task_ref)]
+        id: TaskRef,
+        /// How far to move the due date (e.g., 3d, 2w), counted from the due date,
+        /// or from today if the task is overdue
+        #[arg(long, default_value = "1w")]
+        by: String,
+        /// Then move on to the next day with the due date's weekday
+        /// (keeps "every Tuesday" tasks on Tuesdays)
         #[arg(long)]
-        iced: bool,
-        /// Show all tasks
+        same_weekday: bool,
+    },
+
+    /// Exit with status 0 if the task exists (and is in --state), 1 otherwise; prints nothing
+    Exists {
+        /// Task ID
+        #[arg(value_parser = parse_task_ref)]
+        id: TaskRef,
+        /// Also require the task to be in this state
+        #[arg(long, value_enum)]
+        state: Option<TaskState>,
+        /// Say what was found
+        #[arg(short, long)]
+        verbose: bool,
+    },
+
+    /// Open a task's URL in the default browser
+    OpenUrl {
+        /// Task ID
+        #[arg(value_parser = parse_task_ref)]
+        id: TaskRef,
+    },
+
+    /// Show task details
+    Show {
+        /// Task ID
+        #[arg(value_parser = parse_task_ref)]
+        id: TaskRef,
+        /// Print the task as JSON
         #[arg(long)]
-        all: bool,
+        json: bool,
+        /// With --json, write dates as days since 1970-01-01
+        #[arg(long, requires = "json")]
+        plain_dates: bool,
+        /// Also list the task's recorded transitions, oldest first
+        #[arg(long, conflicts_with = "json")]
+        history: bool,
     },
 
+    /// List tasks
+    List(ListArgs),
+
     /// Set task to ready state (Melting/Iced -> Melted)
+    #[command(visible_alias = "thaw")]
     Warm {
         /// Task ID
-        id: u32,
+        #[arg(
+            required_unless_present = "all",
+            conflicts_with = "all",
+            value_parser = parse_task_ref
+        )]
+        id: Option<TaskRef>,
+        /// Thaw every Iced task now, whatever its thaw date (Melting tasks are left alone)
+        #[arg(long)]
+        all: bool,
     },
 
     /// Complete (evaporate) a task (Melted/Iced -> Evaporated)
     Burn {
         /// Task ID
-        id: u32,
+        #[arg(
+            required_unless_present = "all_melted",
+            conflicts_with = "all_melted",
+            value_parser = parse_task_ref
+        )]
+        id: Option<TaskRef>,
+        /// Burn every Melted task instead of a single ID (Melting and Iced tasks are left alone)
+        #[arg(long)]
+        all_melted: bool,
+        /// Start thawing (Iced -> Melting) the tasks this burn unblocks
+        #[arg(long)]
+        cascade: bool,
+        /// Burn tasks even though they still have open subtasks
+        #[arg(long)]
+        ignore_subtasks: bool,
+    },
+
+    /// List tasks whose reminder lead time before the due date has started (see add --remind)
+    Reminders,
+
+    /// Print one actionable (unblocked Melted) task picked at random
+    Random {
+        /// Seed for a repeatable pick (default: fresh from the system)
+        #[arg(long)]
+        seed: Option<u64>,
     },
 
-    /// Cancel completion (Evaporated -> Melted)
+    /// Burn the next task (highest priority, then soonest due) and show the one after it
+    Pop,
+
+    /// Cancel completion (Evaporated -> state before burn)
+    #[command(visible_alias = "reopen")]
     Cool {
         /// Task ID
-        id: u32,
+        #[arg(value_parser = parse_task_ref)]
+        id: TaskRef,
+    },
+
+    /// Take back a burn still within its grace period (see defaults.burn_grace_days)
+    Unburn {
+        /// Task ID
+        #[arg(value_parser = parse_task_ref)]
+        id: TaskRef,
     },
 
     /// Refreeze a task (-> Iced)
     Freeze {
         /// Task ID
-        id: u32,
+        #[arg(
+            required_unless_present = "state",
+            conflicts_with = "state",
+            value_parser = parse_task_ref
+        )]
+        id: Option<TaskRef>,
         /// Thaw date (e.g., 3d, 1w, 2026-03-01)
         #[arg(short = 'd', long = "date")]
         thaw_date: Option<String>,
+        /// Freeze every task in this state instead of a single ID
+        #[arg(long, value_enum)]
+        state: Option<TaskState>,
+        /// Also freeze the Melting and Melted tasks that (transitively) depend on this one
+        #[arg(long, conflicts_with = "state")]
+        cascade: bool,
+    },
+
+    /// Edit the tasks file in $EDITOR, validating it before it replaces the store
+    #[command(alias = "edit-file")]
+    Open {
+        /// Only print the absolute path of the tasks file
+        #[arg(long)]
+        print: bool,
+    },
+
+    /// Revert the last change to the task list
+    Undo {
+        /// Number of changes to revert at once
+        #[arg(
+            default_value_t = 1,
+            conflicts_with = "list",
+            value_parser = clap::value_parser!(u16).range(1..)
+        )]
+        steps: u16,
+        /// List the available undo steps instead of reverting
+        #[arg(long)]
+        list: bool,
+    },
+
+    /// Export tasks to stdout
+    Export {
+        /// Output format (ics: an all-day calendar event per task with a due date)
+        #[arg(long, value_enum, default_value_t = ExportFormat::Json)]
+        format: ExportFormat,
+        /// With the json format, write dates as days since 1970-01-01
+        #[arg(long)]
+        plain_dates: bool,
+    },
+
+    /// Print the JSON Schema of the tasks file
+    Schema,
+
+    /// Show task counts per state
+    Stats {
+        /// Instead, chart completed tasks per ISO week over the last N weeks (default 8)
+        #[arg(long, value_name = "N", num_args = 0..=1, default_missing_value = "8")]
+        by_week: Option<u32>,
+    },
+
+    /// List tasks completed within a date range, with their total estimate
+    Report {
+        /// First day of the range (e.g., 2026-03-01)
+        #[arg(long)]
+        from: String,
+        /// Last day of the range, inclusive (defaults to today)
+        #[arg(long)]
+        to: Option<String>,
+    },
+
+    /// Walk through Melting and Melted tasks one at a time, choosing what to do with each
+    Triage,
+
+    /// Print only the number of unfinished tasks due today or overdue (for scripts)
+    CountDueToday,
+
+    /// Print a one-line status for shell prompts and status bars (see defaults.summary_format)
+    Summary,
+
+    /// Print the state machine: which command moves a task from which state to which
+    #[command(visible_alias = "state-graph")]
+    States,
+
+    /// Check a tasks file (e.g. before importing or committing it) without touching the task list
+    Validate {
+        /// Path of the JSON tasks file
+        file: std::path::PathBuf,
+    },
+
+    /// Inspect the configuration file
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
     },
+
+    /// Show the dependency graph of tasks
+    Graph {
+        /// Only show the graph below this task
+        id: Option<u32>,
+        /// Output Graphviz DOT instead of an indented tree
+        #[arg(long)]
+        dot: bool,
+        /// Maximum depth to descend into dependencies
+        #[arg(long)]
+        depth: Option<usize>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigAction {
+    /// Validate config.toml (including unknown keys) without touching any task data
+    Check,
+    /// Change one setting in config.toml, creating the file if needed (e.g. defaults.thaw_days 14)
+    Set {
+        /// Qualified key, e.g. defaults.thaw_days or storage.data_file
+        key: String,
+        value: String,
+    },
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// The raw task list as JSON
+    Json,
+    /// iCalendar (.ics) events for due dates
+    Ics,
+}
+
+/// Field that `list` orders tasks by (also accepted in `defaults.sort` in the config)
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SortKey {
+    Id,
+    Title,
+    /// Coldest first: Iced, Melting, Melted, Evaporated
+    State,
+    /// Highest first
+    Priority,
+    /// Thaw date; tasks without one come last
+    Thaw,
+    /// Due date; tasks without one come last
+    Due,
+    Created,
+    /// Shuffled (reproducibly with --seed)
+    Random,
+}
+
+/// One key of a `--sort` list with its direction: "due" or "due:desc" ("asc" is the default)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SortSpec {
+    pub key: SortKey,
+    pub descending: bool,
+}
+
+impl From<SortKey> for SortSpec {
+    fn from(key: SortKey) -> Self {
+        SortSpec {
+            key,
+            descending: false,
+        }
+    }
+}
+
+impl std::str::FromStr for SortSpec {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        let (name, direction) = s.trim().split_once(':').unwrap_or((s.trim(), "asc"));
+        let key = SortKey::from_str(name, true).map_err(|_| {
+            let keys: Vec<String> = SortKey::value_variants()
+                .iter()
+                .filter_map(|k| k.to_possible_value().map(|v| v.get_name().to_string()))
+                .collect();
+            format!("Unknown sort key '{name}' (expected one of {})", keys.join(", "))
+        })?;
+        let descending = match direction {
+            "asc" => false,
+            "desc" => true,
+            _ => return Err(format!("Unknown sort direction '{direction}' (expected asc or desc)")),
+        };
+        Ok(SortSpec { key, descending })
+    }
+}
+
+/// Parses a comma-separated sort list such as "priority,due:desc,id"
+pub fn parse_sort_list(s: &str) -> Result<Vec<SortSpec>, String> {
+    s.split(',').map(parse_sort_spec).collect()
+}
+
+fn parse_sort_spec(s: &str) -> Result<SortSpec, String> {
+    s.parse()
+}
+
+/// Column of the `list` table, selected with `--fields`
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ListField {
+    Id,
+    Title,
+    State,
+    Thaw,
+    Due,
+    Created,
+}
+
+/// Output of `list`
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ListFormat {
+    /// Aligned columns for the terminal
+    Table,
+    /// Pipe-delimited Markdown table without colors
+    Markdown,
+}
+
+/// What `list --group-by` groups tasks by
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GroupBy {
+    State,
+    /// Tasks with several tags appear under each; untagged tasks under "(none)"
+    Tag,
+}
+
+/// Order of the groups for `list --group-by`
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GroupSort {
+    /// By state (coldest first) or by tag name, with "(none)" last
+    Name,
+    /// Largest group first
+    Count,
+}
+
+/// How `list --watch --notify` alerts about thawed tasks
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NotifyMode {
+    /// Ring the terminal bell
+    Bell,
+    /// Show a desktop notification (requires the `desktop-notify` feature)
+    Desktop,
+}
+
+#[derive(Args, Debug)]
+pub struct AddArgs {
+    /// Task title
+    #[arg(value_parser = parse_title)]
+    pub title: String,
+    /// Task description
+    #[arg(long = "desc", value_parser = parse_description)]
+    pub description: Option<String>,
+    /// Thaw date (e.g., 3d, 1w, 2026-03-01). If specified, the task is created in Iced state.
+    #[arg(short = 'd', long = "date")]
+    pub thaw_date: Option<String>,
+    /// Due date (e.g., 3d, 1w, 2026-03-01)
+    #[arg(long = "due")]
+    pub due_date: Option<String>,
+    /// ID of a task this one depends on (can be repeated)
+    #[arg(long = "depends")]
+    pub depends_on: Vec<u32>,
+    /// Sequence the task after another one (shorthand for --depends <ID>)
+    #[arg(long, value_name = "ID")]
+    pub after: Option<u32>,
+    /// Without --due, copy the due date of the dependency (the earliest one if several have one)
+    #[arg(long)]
+    pub inherit_due: bool,
+    /// Take the due date from a trailing "by <date>" or "due <date>" in the title
+    /// (e.g., "Submit report by fri"), removing the phrase from the title
+    #[arg(long, conflicts_with = "due_date")]
+    pub due_from_title: bool,
+    /// Priority (high-priority titles are shown in bold)
+    #[arg(short, long, value_enum, default_value_t = Priority::Normal)]
+    pub priority: Priority,
+    /// Tag the task (can be repeated; tags are case-insensitive)
+    #[arg(long = "tag")]
+    pub tags: Vec<String>,
+    /// Link to a related ticket, PR or document (e.g., https://...)
+    #[arg(long, value_parser = parse_url)]
+    pub url: Option<String>,
+    /// Estimated effort (e.g., 45m, 2h, 1h30m)
+    #[arg(long, value_parser = parse_estimate)]
+    pub estimate: Option<u32>,
+    /// List the task in `reminders` this long before its due date (e.g., 2d, 1w)
+    #[arg(long, value_name = "SPAN", value_parser = parse_remind)]
+    pub remind: Option<String>,
+    /// Refuse to add the task if an active task already has the same title (ignoring case and surrounding spaces)
+    #[arg(long)]
+    pub unique: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct ListArgs {
+    /// Show frozen (Iced) tasks
+    #[arg(long)]
+    pub iced: bool,
+    /// Show all tasks
+    #[arg(long)]
+    pub all: bool,
+    /// Show only Melting tasks (the inbox waiting for a decision)
+    #[arg(long, conflicts_with_all = ["iced", "all", "melted"])]
+    pub melting: bool,
+    /// Show only Melted tasks (ready to work on)
+    #[arg(long, conflicts_with_all = ["iced", "all"])]
+    pub melted: bool,
+    /// Only show tasks whose ID is in this inclusive range (e.g., 10-20)
+    #[arg(long, value_parser = parse_id_range)]
+    pub id_range: Option<(u32, u32)>,
+    /// Only show tasks created on this day (e.g., today, 2026-01-15)
+    #[arg(long)]
+    pub created_on: Option<String>,
+    /// Only show tasks changed on or after this day (e.g., 3d for three days ago, 2026-01-01)
+    #[arg(long, value_name = "DATE")]
+    pub changed_since: Option<String>,
+    /// Only show tasks with at least this priority
+    #[arg(long, value_enum, value_name = "PRIORITY")]
+    pub min_priority: Option<Priority>,
+    /// Only show tasks without a due date
+    #[arg(long, alias = "due-date-missing", conflicts_with = "has_due")]
+    pub no_due: bool,
+    /// Only show tasks with a due date
+    #[arg(long)]
+    pub has_due: bool,
+    /// Only show tasks with this tag (can be repeated: tasks need every one; case-insensitive)
+    #[arg(long = "tag", value_name = "TAG")]
+    pub tags: Vec<String>,
+    /// Hide tasks with this tag (can be repeated; case-insensitive)
+    #[arg(long = "not-tag", visible_alias = "not-tagged", value_name = "TAG")]
+    pub not_tags: Vec<String>,
+    /// Only show tasks whose title or description contains this text (case-insensitive)
+    #[arg(long, value_name = "TEXT")]
+    pub contains: Option<String>,
+    /// Print how many tasks are left after each filter (e.g. "all: 120 → state: 40 → tag work: 12")
+    #[arg(long)]
+    pub explain_filter: bool,
+    /// Only show active tasks overdue by at least this long (e.g., 7d, 2w)
+    #[arg(long, value_name = "SPAN", value_parser = parse_day_span)]
+    pub overdue_by: Option<u32>,
+    /// Show at most this many tasks
+    #[arg(long)]
+    pub limit: Option<usize>,
+    /// Skip this many tasks (after sorting) before showing any
+    #[arg(long, default_value_t = 0)]
+    pub offset: usize,
+    /// Render a sub-table per group
+    #[arg(long, value_enum)]
+    pub group_by: Option<GroupBy>,
+    /// Order of the groups with --group-by
+    #[arg(long, value_enum, default_value_t = GroupSort::Name, requires = "group_by")]
+    pub group_sort: GroupSort,
+    /// Print only the task rows, without the column header and rule
+    #[arg(long)]
+    pub no_header: bool,
+    /// Show a thermometer glyph that heats up as the due date nears (red when overdue)
+    #[arg(long)]
+    pub temp: bool,
+    /// Columns to show, in order (e.g., id,title,due)
+    #[arg(long, value_enum, value_delimiter = ',')]
+    pub fields: Option<Vec<ListField>>,
+    /// Fit the table into this many columns instead of the detected terminal width
+    #[arg(long)]
+    pub width: Option<usize>,
+    /// Sort by these fields in order, each optionally ":desc" (e.g., priority,due:desc,id).
+    /// Defaults to `defaults.sort` in the config, else the stored order
+    #[arg(long, value_name = "KEYS", value_delimiter = ',', value_parser = parse_sort_spec)]
+    pub sort: Vec<SortSpec>,
+    /// Reverse the display order
+    #[arg(long)]
+    pub reverse: bool,
+    /// Seed for `--sort random`, for a repeatable order (default: fresh from the system)
+    #[arg(long)]
+    pub seed: Option<u64>,
+    /// Show tasks from every list in the data directory (the main list and *.json), with a
+    /// List column
+    #[arg(long)]
+    pub all_lists: bool,
+    /// Print the selected tasks as a JSON array instead of a table
+    #[arg(long, group = "json_output")]
+    pub json: bool,
+    /// Like --json, with fields computed against today added to each task: is_overdue,
+    /// days_until_due, days_until_thaw, age_days and is_blocked
+    #[arg(long, group = "json_output")]
+    pub json_extended: bool,
+    /// How to print the table: aligned text, or a Markdown table for issues and notes
+    #[arg(long, value_enum, default_value_t = ListFormat::Table, conflicts_with = "json_output")]
+    pub format: ListFormat,
+    /// With --json, write dates as days since 1970-01-01
+    #[arg(long, requires = "json_output")]
+    pub plain_dates: bool,
+    /// With --json, write the array on a single line without whitespace
+    #[arg(long, requires = "json_output")]
+    pub compact: bool,
+    /// Redraw the table every N seconds (default 60) until interrupted with Ctrl-C
+    #[arg(
+        long,
+        value_name = "SECS",
+        num_args = 0..=1,
+        default_missing_value = "60",
+        conflicts_with = "json_output",
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    pub watch: Option<u64>,
+    /// With --watch, alert when tasks thaw: a terminal bell (default) or a desktop notification
+    #[arg(
+        long,
+        value_enum,
+        num_args = 0..=1,
+        default_missing_value = "bell",
+        requires = "watch"
+    )]
+    pub notify: Option<NotifyMode>,
+}
+
+#[derive(Args, Debug)]
+pub struct EditArgs {
+    /// Task ID
+    #[arg(value_parser = parse_task_ref)]
+    pub id: TaskRef,
+    /// New title
+    #[arg(short = 't', long = "title", value_parser = parse_title)]
+    pub title: Option<String>,
+    /// New description
+    #[arg(long = "desc", value_parser = parse_description)]
+    pub description: Option<String>,
+    /// Change the thaw date (e.g., 3d, 1w, 2026-03-01; +3d or -1w moves the current one)
+    #[arg(short = 'd', long = "date", allow_hyphen_values = true)]
+    pub thaw_date: Option<String>,
+    /// Change the due date (e.g., 3d, 1w, 2026-03-01; +3d or -1w moves the current one)
+    #[arg(long = "due", allow_hyphen_values = true)]
+    pub due_date: Option<String>,
+    /// Add a dependency on another task (can be repeated)
+    #[arg(long = "depends")]
+    pub depends_on: Vec<u32>,
+    /// Change the priority
+    #[arg(short, long, value_enum)]
+    pub priority: Option<Priority>,
+    /// Add a tag (can be repeated)
+    #[arg(long = "tag")]
+    pub tags: Vec<String>,
+    /// Remove a tag (can be repeated)
+    #[arg(long = "untag")]
+    pub untags: Vec<String>,
+    /// Change the linked URL ("" removes it)
+    #[arg(long, value_parser = parse_url_or_empty)]
+    pub url: Option<String>,
+    /// Change the estimated effort (e.g., 45m, 2h; "0m" removes it)
+    #[arg(long, value_parser = parse_estimate)]
+    pub estimate: Option<u32>,
+    /// Change the reminder lead time before the due date (e.g., 2d; "" removes it)
+    #[arg(long, value_name = "SPAN", value_parser = parse_remind)]
+    pub remind: Option<String>,
+    /// Add a checklist item (can be repeated)
+    #[arg(long = "subtask", value_name = "TEXT")]
+    pub subtasks: Vec<String>,
+    /// Check off the subtask with this number, as shown by `show` (can be repeated)
+    #[arg(long, value_name = "N")]
+    pub check: Vec<usize>,
+    /// Uncheck the subtask with this number (can be repeated)
+    #[arg(long, value_name = "N")]
+    pub uncheck: Vec<usize>,
+    /// After the other edits, move the task to this named list under a new ID
+    /// (its dependencies stay behind)
+    #[arg(long, value_name = "LIST", conflicts_with = "depends_on")]
+    pub move_to: Option<String>,
+}
+
+/// A task ID as typed on the command line: "3", or "W-3" for task 3 of the list
+/// whose prefix is "W" (`[lists.<name>] prefix` in the config)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TaskRef {
+    pub prefix: Option<String>,
+    pub id: u32,
+}
+
+fn parse_task_ref(s: &str) -> Result<TaskRef, String> {
+    let s = s.trim();
+    let (prefix, id) = match s.split_once('-') {
+        Some((prefix, id)) if !prefix.is_empty() => (Some(prefix.to_string()), id),
+        _ => (None, s),
+    };
+    let id = id
+        .parse()
+        .map_err(|_| format!("Invalid task ID '{s}' (expected e.g. 3 or W-3)"))?;
+    Ok(TaskRef { prefix, id })
+}
+
+/// Parses an inclusive ID range such as "10-20"
+fn parse_id_range(s: &str) -> Result<(u32, u32), String> {
+    let (start, end) = s
+        .split_once('-')
+        .ok_or_else(|| format!("Invalid ID range '{s}' (expected <start>-<end>)"))?;
+    let start: u32 = start
+        .trim()
+        .parse()
+        .map_err(|_| format!("Invalid range start '{start}'"))?;
+    let end: u32 = end
+        .trim()
+        .parse()
+        .map_err(|_| format!("Invalid range end '{end}'"))?;
+    if start > end {
+        return Err(format!("Invalid ID range '{s}': start must not exceed end"));
+    }
+    Ok((start, end))
+}
+
+/// Parses a span of days such as "7d" or "2w" into a number of days
+fn parse_day_span(s: &str) -> Result<u32, String> {
+    crate::models::parse_day_span(s).map_err(|e| e.to_string())
+}
+
+/// A title with control characters (e.g. a pasted tab) replaced by spaces
+fn parse_title(s: &str) -> Result<String, String> {
+    Ok(crate::models::clean_title(s))
+}
+
+/// A description with control characters other than line breaks replaced by spaces
+fn parse_description(s: &str) -> Result<String, String> {
+    Ok(crate::models::clean_description(s))
+}
+
+/// A reminder lead time such as "2d", kept as written; empty clears it (with `edit`)
+fn parse_remind(s: &str) -> Result<String, String> {
+    let s = s.trim();
+    if !s.is_empty() {
+        parse_day_span(s)?;
+    }
+    Ok(s.to_string())
+}
+
+/// Accepts a URL with a scheme, such as "https://example.com" or "mailto:me@example.com"
+fn parse_url(s: &str) -> Result<String, String> {
+    let s = s.trim();
+    let valid = s.split_once(':').is_some_and(|(scheme, rest)| {
+        scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+            && scheme
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+            && !rest.is_empty()
+    });
+    if !valid {
+        return Err(format!("'{s}' does not look like a URL (expected a scheme such as https://)"));
+    }
+    Ok(s.to_string())
+}
+
+/// Like `parse_url`, but an empty string is allowed (used to clear the URL)
+fn parse_url_or_empty(s: &str) -> Result<String, String> {
+    if s.trim().is_empty() {
+        Ok(String::new())
+    } else {
+        parse_url(s)
+    }
+}
+
+/// Effort estimate in minutes, from "45m", "2h" or "1h30m"
+fn parse_estimate(s: &str) -> Result<u32, String> {
+    crate::models::parse_duration(s).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_urls_require_a_scheme() {
+        assert_eq!(parse_url(" https://example.com/pr/1 "), Ok("https://example.com/pr/1".to_string()));
+        assert!(parse_url("mailto:me@example.com").is_ok());
+        assert!(parse_url("example.com").is_err());
+        assert!(parse_url("://example.com").is_err());
+        assert!(parse_url("https:").is_err());
+        assert_eq!(parse_url_or_empty(""), Ok(String::new()));
+    }
+
+    #[test]
+    fn parse_task_refs() {
+        assert_eq!(parse_task_ref("3"), Ok(TaskRef { prefix: None, id: 3 }));
+        assert_eq!(
+            parse_task_ref("W-12"),
+            Ok(TaskRef { prefix: Some("W".to_string()), id: 12 })
+        );
+        assert!(parse_task_ref("W-").is_err());
+        assert!(parse_task_ref("-3").is_err());
+        assert!(parse_task_ref("W3").is_err());
+    }
+
+    #[test]
+    fn parse_sort_lists() {
+        assert_eq!(
+            parse_sort_list("priority,Due:desc"),
+            Ok(vec![
+                SortSpec::from(SortKey::Priority),
+                SortSpec {
+                    key: SortKey::Due,
+                    descending: true
+                }
+            ])
+        );
+        assert!(parse_sort_list("size").unwrap_err().contains("priority"));
+        assert!(parse_sort_list("due:down").is_err());
+    }
+
+    #[test]
+    fn parse_valid_id_range() {
+        assert_eq!(parse_id_range("10-20"), Ok((10, 20)));
+        assert_eq!(parse_id_range("5-5"), Ok((5, 5)));
+    }
+
+    #[test]
+    fn parse_invalid_id_range() {
+        assert!(parse_id_range("20-10").is_err());
+        assert!(parse_id_range("10").is_err());
+        assert!(parse_id_range("a-b").is_err());
+        assert!(parse_id_range("-5").is_err());
+    }
+
+    #[test]
+    fn parse_day_spans() {
+        assert_eq!(parse_day_span("7d"), Ok(7));
+        assert_eq!(parse_day_span("2w"), Ok(14));
+        assert!(parse_day_span("7").is_err());
+        assert!(parse_day_span("-1d").is_err());
+    }
 }
@@ -1,30 +1,201 @@
-use anyhow::Result;
-use chrono::Local;
-use colored::Colorize;
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::io::{self, IsTerminal, Write};
+use std::process::ExitCode;
+use std::sync::OnceLock;
 
-use crate::cli::Commands;
-use crate::config::Config;
-use crate::models::{parse_date_spec, Task, TaskState};
+use anyhow::{bail, Context, Result};
+use chrono::Datelike;
+use colored::{Color, ColoredString, Colorize};
+use rand::rngs::StdRng;
+use rand::seq::{IndexedRandom, SliceRandom};
+use rand::SeedableRng;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use crate::cli::{
+    AddArgs, Commands, ConfigAction, EditArgs, ExportFormat, GroupBy, GroupSort, ListArgs,
+    ListField, ListFormat, SortKey, SortSpec, TaskRef,
+};
+use crate::check;
+use crate::config::{self, Config, DisplayConfig, StaleStyle, StorageBackend, Theme, ThawTarget};
+use crate::deps;
+use crate::ics;
+use crate::json_color;
+use crate::models::{self, parse_date_spec, Priority, Subtask, Task, TaskState};
+use crate::notify;
 use crate::state;
-use crate::storage::TaskStore;
+use crate::storage::{self, JsonStore, TaskStore};
+
+/// Colors for the task states and due-date urgency; `None` leaves the text unstyled
+#[derive(Debug, Clone, Copy)]
+struct Palette {
+    iced: Option<Color>,
+    melting: Option<Color>,
+    melted: Option<Color>,
+    evaporated: Option<Color>,
+    overdue: Option<Color>,
+    near_due: Option<Color>,
+}
+
+const fn rgb(r: u8, g: u8, b: u8) -> Option<Color> {
+    Some(Color::TrueColor { r, g, b })
+}
+
+const DARK_PALETTE: Palette = Palette {
+    iced: rgb(0xBB, 0xE8, 0xF2),
+    melting: rgb(0x94, 0xD7, 0xF2),
+    melted: rgb(0x55, 0xB3, 0xD9),
+    evaporated: rgb(0x3F, 0x5F, 0x73),
+    overdue: Some(Color::Red),
+    near_due: rgb(0x55, 0xB3, 0xD9),
+};
+
+const LIGHT_PALETTE: Palette = Palette {
+    iced: rgb(0x4A, 0x90, 0xB0),
+    melting: rgb(0x1F, 0x6F, 0xA8),
+    melted: rgb(0x0B, 0x4A, 0x85),
+    evaporated: rgb(0x8A, 0x8F, 0x94),
+    overdue: rgb(0xC0, 0x1C, 0x28),
+    near_due: rgb(0x0B, 0x4A, 0x85),
+};
+
+const MONO_PALETTE: Palette = Palette {
+    iced: None,
+    melting: None,
+    melted: None,
+    evaporated: None,
+    overdue: None,
+    near_due: None,
+};
+
+/// The palette chosen by the config, set once in `execute`
+static PALETTE: OnceLock<Palette> = OnceLock::new();
+
+impl Palette {
+    /// The configured theme with the `[colors]` overrides applied
+    fn from_config(config: &Config) -> Result<Self> {
+        let mut palette = match config.defaults.theme {
+            Theme::Dark => DARK_PALETTE,
+            Theme::Light => LIGHT_PALETTE,
+            Theme::Mono => MONO_PALETTE,
+        };
+        let colors = &config.colors;
+        for (slot, value) in [
+            (&mut palette.iced, &colors.iced),
+            (&mut palette.melting, &colors.melting),
+            (&mut palette.melted, &colors.melted),
+            (&mut palette.evaporated, &colors.evaporated),
+            (&mut palette.overdue, &colors.overdue),
+            (&mut palette.near_due, &colors.near_due),
+        ] {
+            if let Some(value) = value {
+                let (r, g, b) = config::parse_hex_color(value)?;
+                *slot = rgb(r, g, b);
+            }
+        }
+        Ok(palette)
+    }
+
+    fn current() -> &'static Palette {
+        PALETTE.get_or_init(|| DARK_PALETTE)
+    }
+
+    fn state(&self, state: TaskState) -> Option<Color> {
+        match state {
+            TaskState::Iced => self.iced,
+            TaskState::Melting => self.melting,
+            TaskState::Melted => self.melted,
+            TaskState::Evaporated => self.evaporated,
+        }
+    }
+}
+
+/// Applies a palette color, if any
+fn paint(text: &str, color: Option<Color>) -> ColoredString {
+    match color {
+        Some(color) => text.color(color),
+        None => text.normal(),
+    }
+}
+
+/// A one-character "thermometer" for `list --temp`, hotter as the due date nears.
+/// Tasks without a due date get a neutral dot.
+fn temperature_glyph(due_date: Option<chrono::NaiveDate>, today: chrono::NaiveDate) -> String {
+    let palette = Palette::current();
+    let glyph = temperature_char(due_date, today);
+    match due_date.map(|due| (due - today).num_days()) {
+        None => paint(glyph, palette.evaporated).to_string(),
+        Some(days) if days < 0 => paint(glyph, palette.overdue).bold().to_string(),
+        Some(0..=3) => paint(glyph, palette.near_due).to_string(),
+        Some(4..=14) => paint(glyph, palette.melting).to_string(),
+        Some(_) => paint(glyph, palette.iced).to_string(),
+    }
+}
+
+/// The uncolored thermometer character
+fn temperature_char(due_date: Option<chrono::NaiveDate>, today: chrono::NaiveDate) -> &'static str {
+    match due_date.map(|due| (due - today).num_days()) {
+        None => "·",
+        Some(days) if days < 0 => "▇",
+        Some(0..=3) => "▅",
+        Some(4..=14) => "▃",
+        Some(_) => "▁",
+    }
+}
 
 /// Returns a colored string based on the task state
 fn colored_state(state: TaskState) -> String {
-    let label = state.to_string();
-    match state {
-        TaskState::Iced => label.truecolor(0xBB, 0xE8, 0xF2).to_string(),
-        TaskState::Melting => label.truecolor(0x94, 0xD7, 0xF2).to_string(),
-        TaskState::Melted => label.truecolor(0x55, 0xB3, 0xD9).to_string(),
-        TaskState::Evaporated => label.truecolor(0x3F, 0x5F, 0x73).to_string(),
-    }
+    paint(&state.to_string(), Palette::current().state(state)).to_string()
 }
 
 /// Pads a colored string to a specified width (adds spaces outside the ANSI codes)
 fn colored_state_padded(state: TaskState, width: usize) -> String {
-    let visible_len = state.to_string().len();
-    let colored = colored_state(state);
-    let padding = width.saturating_sub(visible_len);
-    format!("{colored}{}", " ".repeat(padding))
+    let padding = width.saturating_sub(state.to_string().width());
+    format!("{}{}", colored_state(state), " ".repeat(padding))
+}
+
+/// Left-aligns `s` in `width` terminal columns. Unlike `{:<width$}`, which counts chars,
+/// this accounts for wide (CJK, emoji) and zero-width (combining) characters.
+fn pad(s: &str, width: usize) -> String {
+    format!("{s}{}", " ".repeat(width.saturating_sub(s.width())))
+}
+
+/// Shortens a string to at most `width` terminal columns, marking the cut with "…"
+fn truncate(s: &str, width: usize) -> String {
+    if s.width() <= width {
+        return s.to_string();
+    }
+    let budget = width.saturating_sub(1);
+    let mut used = 0;
+    let mut out = String::new();
+    for c in s.chars() {
+        used += c.width().unwrap_or(0);
+        if used > budget {
+            break;
+        }
+        out.push(c);
+    }
+    out.push('…');
+    out
+}
+
+/// Makes `url` clickable with an OSC 8 escape when writing styled output to a terminal
+fn hyperlink(url: &str) -> String {
+    if io::stdout().is_terminal() && colored::control::SHOULD_COLORIZE.should_colorize() {
+        format!("\x1b]8;;{url}\x1b\\{url}\x1b]8;;\x1b\\")
+    } else {
+        url.to_string()
+    }
+}
+
+/// Prints `value` as pretty JSON, syntax-colored when writing styled output to a terminal
+fn print_json(value: &serde_json::Value) -> Result<()> {
+    if io::stdout().is_terminal() && colored::control::SHOULD_COLORIZE.should_colorize() {
+        println!("{}", json_color::colorize(value));
+    } else {
+        println!("{}", serde_json::to_string_pretty(value)?);
+    }
+    Ok(())
 }
 
 /// Converts a date to a string (None becomes "-")
@@ -32,67 +203,336 @@ fn date_str(date: Option<chrono::NaiveDate>) -> String {
     date.map(|d| d.to_string()).unwrap_or_else(|| "-".to_string())
 }
 
-/// Main dispatcher for command execution
-pub fn execute(command: Commands) -> Result<()> {
-    let config = Config::load()?;
-    let store = TaskStore::from_config(&config)?;
-    let today = Local::now().date_naive();
+/// Asks a yes/no question on stdin; anything but "y"/"yes" counts as no.
+/// `yes` (the global --yes/--force) answers for the user. Every prompt goes through here,
+/// so that one flag skips them all. Without a terminal to ask on, the answer is no.
+fn confirm(prompt: &str, yes: bool) -> Result<bool> {
+    if yes {
+        return Ok(true);
+    }
+    print!("{prompt} [y/N] ");
+    if !io::stdin().is_terminal() {
+        println!();
+        println!("Not asking: stdin is not a terminal (pass --yes to confirm).");
+        return Ok(false);
+    }
+    io::stdout().flush()?;
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+/// Confirmation for destructive commands, asked only when `defaults.confirm_destructive` is on
+fn confirm_destructive(config: &Config, yes: bool, prompt: &str) -> Result<bool> {
+    if !config.defaults.confirm_destructive {
+        return Ok(true);
+    }
+    confirm(prompt, yes)
+}
+
+/// Loads the task list, makes burns past their grace period final, applies auto_warm and,
+/// when configured, purges long-Evaporated tasks.
+/// Changes are saved right away so they persist even if the command fails afterwards.
+fn load_and_thaw(store: &TaskStore, today: chrono::NaiveDate) -> Result<Vec<Task>> {
+    let mut tasks = store.load()?;
+    // Burns past their grace period become final before the purge looks at them
+    let committed = state::commit_burns(&mut tasks, today);
+    if let Some(days) = store.auto_purge_days() {
+        let purged = state::purge_evaporated(&mut tasks, today, days);
+        if purged > 0 {
+            // A notice on stderr keeps --json output clean; the purge is undoable
+            let noun = if purged == 1 { "task" } else { "tasks" };
+            eprintln!("Purged {purged} {noun} evaporated at least {days} days ago.");
+            store.save(&tasks)?;
+        }
+    }
+    let warmed = state::auto_warm(&mut tasks, today, store.thaw_to());
+    if !warmed.is_empty() || committed > 0 {
+        // Thawing is automatic and would simply happen again, so it is not an undo step
+        store.save_untracked(&tasks)?;
+    }
+    Ok(tasks)
+}
+
+/// Main dispatcher for command execution. `in_list` names the list chosen with `--in`;
+/// otherwise a list prefix on the task IDs picks it. Returns the exit code for `main`:
+/// a failure without an error message when `exists` finds no task.
+pub fn execute(
+    command: Commands,
+    yes: bool,
+    thaw_to: Option<ThawTarget>,
+    in_list: Option<String>,
+) -> Result<ExitCode> {
+    // Checking the config must work even when the config is broken, and never opens the store.
+    // The same goes for setting a value, which may be what repairs it.
+    if let Commands::Config { action } = &command {
+        return match action {
+            ConfigAction::Check => cmd_config_check(),
+            ConfigAction::Set { key, value } => cmd_config_set(key, value),
+        }
+        .map(|()| ExitCode::SUCCESS);
+    }
+    // Validating a file must not depend on the config or open the task list either
+    if let Commands::Validate { file } = &command {
+        return cmd_validate(file).map(|()| ExitCode::SUCCESS);
+    }
+
+    let mut config = Config::load()?;
+    let list = match (in_list, target_list(&command, &config)?) {
+        (Some(chosen), Some(addressed)) if chosen != addressed => {
+            bail!("The task IDs are in list '{addressed}', not '{chosen}' (given with --in)")
+        }
+        (Some(chosen), _) => Some(chosen),
+        (None, addressed) => addressed.map(str::to_string),
+    };
+    if let Some(name) = &list {
+        config.select_list(name);
+    }
+    if let Some(thaw_to) = thaw_to {
+        config.defaults.thaw_to = thaw_to;
+    }
+    PALETTE.get_or_init(|| Palette::from_config(&config).unwrap_or(DARK_PALETTE));
+    let store = match &list {
+        Some(name) => TaskStore::for_list(&config, name)?,
+        None => TaskStore::from_config(&config)?,
+    };
+    let today = config.today()?;
 
     match command {
-        Commands::Add {
-            title,
-            description,
-            thaw_date,
-            due_date,
-        } => cmd_add(
+        Commands::Add(args) => cmd_add(&store, &args, today, &config)?,
+        Commands::Edit(args) => cmd_edit(&store, &args, today, &config)?,
+        Commands::Clone { id, title } => cmd_clone(&store, id.id, title, today)?,
+        Commands::SetId { old, new } => cmd_set_id(&store, old.id, new, today)?,
+        Commands::Delete { id } => cmd_delete(&store, id.id, yes, today)?,
+        Commands::Merge { from, into } => {
+            cmd_merge(&store, from.id, into.id, yes, today, &config)?
+        },
+        Commands::Show {
+            id,
+            json,
+            plain_dates,
+            history,
+        } => cmd_show(&store, id.id, json, plain_dates, history, today, &config)?,
+        Commands::List(args) => match args.watch {
+            Some(secs) => cmd_watch(&store, &args, secs, &config)?,
+            None => cmd_list(&store, &args, today, &config)?,
+        },
+        Commands::Warm { id, all } => cmd_warm(&store, id.map(|r| r.id), all, yes, today)?,
+        Commands::Defer {
+            id,
+            by,
+            same_weekday,
+        } => cmd_defer(&store, id.id, &by, same_weekday, today)?,
+        Commands::Burn {
+            id,
+            all_melted,
+            cascade,
+            ignore_subtasks,
+        } => cmd_burn(
             &store,
-            &title,
-            description.as_deref(),
-            thaw_date.as_deref(),
-            due_date.as_deref(),
+            id.map(|r| r.id).filter(|_| !all_melted),
+            yes,
+            ignore_subtasks,
+            cascade,
             today,
+            config.defaults.burn_grace_days,
         )?,
-        Commands::Edit {
+        Commands::Exists { id, state, verbose } => {
+            if !cmd_exists(&store, id.id, state, verbose, today)? {
+                return Ok(ExitCode::FAILURE);
+            }
+        }
+        Commands::Reminders => cmd_reminders(&store, today, config.defaults.weekday_window)?,
+        Commands::Random { seed } => cmd_random(&store, seed, today)?,
+        Commands::Pop => cmd_pop(&store, yes, today, config.defaults.burn_grace_days)?,
+        Commands::Cool { id } => cmd_cool(&store, id.id, today)?,
+        Commands::Unburn { id } => cmd_unburn(&store, id.id, today)?,
+        Commands::Freeze {
             id,
-            title,
-            description,
             thaw_date,
-            due_date,
-        } => cmd_edit(
-            &store,
-            id,
-            title.as_deref(),
-            description.as_deref(),
-            thaw_date.as_deref(),
-            due_date.as_deref(),
-            today,
-        )?,
-        Commands::Show { id } => cmd_show(&store, id, today)?,
-        Commands::List { iced, all } => cmd_list(&store, iced, all, today)?,
-        Commands::Warm { id } => cmd_warm(&store, id, today)?,
-        Commands::Burn { id } => cmd_burn(&store, id, today)?,
-        Commands::Cool { id } => cmd_cool(&store, id, today)?,
-        Commands::Freeze { id, thaw_date } => {
-            cmd_freeze(&store, id, thaw_date.as_deref(), today, &config)?
+            state,
+            cascade,
+        } => {
+            let target = match (id, state) {
+                (Some(id), _) => FreezeTarget::Task { id: id.id, cascade },
+                (None, Some(state)) => FreezeTarget::State(state),
+                (None, None) => unreachable!("clap requires an ID or --state"),
+            };
+            cmd_freeze(&store, target, yes, thaw_date.as_deref(), today, &config)?
+        }
+        Commands::Open { print } => cmd_open(&store, print, &config)?,
+        Commands::Undo { steps, list } => cmd_undo(&store, steps.into(), list, yes, &config)?,
+        Commands::Export {
+            format,
+            plain_dates,
+        } => cmd_export(&store, format, plain_dates, today)?,
+        Commands::Schema => {
+            println!("{}", serde_json::to_string_pretty(&models::tasks_file_schema())?)
+        }
+        Commands::Graph { id, dot, depth } => cmd_graph(&store, id, dot, depth, today)?,
+        Commands::OpenUrl { id } => cmd_open_url(&store, id.id, today)?,
+        Commands::Stats { by_week } => cmd_stats(&store, by_week, today)?,
+        Commands::Report { from, to } => cmd_report(&store, &from, to.as_deref(), today)?,
+        Commands::Triage => cmd_triage(&store, today, &config)?,
+        Commands::States => print!("{}", render_states()),
+        Commands::Summary => cmd_summary(&store, today, &config)?,
+        Commands::CountDueToday => cmd_count_due_today(&store, today)?,
+        Commands::Config { .. } | Commands::Validate { .. } => {
+            unreachable!("handled before the store is opened")
+        }
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+/// The named list addressed by the prefixed task IDs of `command` ("W-3"), if any.
+/// Every ID of one command must point into the same list.
+fn target_list<'c>(command: &Commands, config: &'c Config) -> Result<Option<&'c str>> {
+    let refs: Vec<&TaskRef> = match command {
+        Commands::Edit(args) => vec![&args.id],
+        Commands::Clone { id, .. }
+        | Commands::OpenUrl { id }
+        | Commands::Show { id, .. }
+        | Commands::Delete { id }
+        | Commands::Defer { id, .. }
+        | Commands::Cool { id }
+        | Commands::Unburn { id }
+        | Commands::Exists { id, .. } => vec![id],
+        Commands::Warm { id, .. } | Commands::Burn { id, .. } | Commands::Freeze { id, .. } => {
+            id.iter().collect()
+        }
+        Commands::Merge { from, into } => vec![from, into],
+        Commands::SetId { old, .. } => vec![old],
+        _ => Vec::new(),
+    };
+    let mut lists = refs
+        .iter()
+        .map(|r| r.prefix.as_deref().map(|p| config.list_for_prefix(p)).transpose());
+    let Some(first) = lists.next().transpose()? else {
+        return Ok(None);
+    };
+    for list in lists {
+        if list? != first {
+            bail!("All task IDs must be in the same list");
+        }
+    }
+    Ok(first)
+}
+
+/// Whether task `id` exists and, if given, is in `in_state`. Read-only like `summary`:
+/// pending thaws count, but are not saved.
+fn cmd_exists(
+    store: &TaskStore,
+    id: u32,
+    in_state: Option<TaskState>,
+    verbose: bool,
+    today: chrono::NaiveDate,
+) -> Result<bool> {
+    let mut tasks = store.load()?;
+    state::auto_warm(&mut tasks, today, store.thaw_to());
+    let task = tasks.iter().find(|t| t.id == id);
+    let found = task.is_some_and(|t| in_state.is_none_or(|s| t.state == s));
+    if verbose {
+        match task {
+            Some(task) => println!("Task {id} exists ({})", task.state),
+            None => println!("Task {id} not found"),
         }
     }
+    Ok(found)
+}
+
+/// Prints `defaults.summary_format` filled with the current counts, without a trailing newline.
+/// Meant to run in every shell prompt, so it loads once and never writes: pending thaws are
+/// counted as thawed without being saved.
+fn cmd_summary(store: &TaskStore, today: chrono::NaiveDate, config: &Config) -> Result<()> {
+    let mut tasks = store.load()?;
+    state::auto_warm(&mut tasks, today, store.thaw_to());
+    let counts = models::SummaryCounts::of(&tasks, today);
+    print!("{}", counts.render(&config.defaults.summary_format)?);
+    io::stdout().flush()?;
+    Ok(())
+}
 
+/// Prints only the number of unfinished tasks due today or earlier. Read-only like `summary`;
+/// Iced tasks count too, so pending thaws make no difference.
+fn cmd_count_due_today(store: &TaskStore, today: chrono::NaiveDate) -> Result<()> {
+    let counts = models::SummaryCounts::of(&store.load()?, today);
+    println!("{}", counts.due_today + counts.overdue);
     Ok(())
 }
 
+/// Renders `state::TRANSITIONS` as one arrow per transition, e.g.
+/// "Iced | Melting  --warm-->  Melted  ready to work on"
+fn render_states() -> String {
+    let names = |states: &[TaskState]| {
+        if states.len() == 4 {
+            "any".to_string()
+        } else {
+            states.iter().map(ToString::to_string).collect::<Vec<_>>().join(" | ")
+        }
+    };
+    let rows: Vec<(String, String, String, &str)> = state::TRANSITIONS
+        .iter()
+        .map(|t| (names(t.from), format!("--{}-->", t.action), names(t.to), t.note))
+        .collect();
+    let width = |i: usize| {
+        rows.iter()
+            .map(|r| [&r.0, &r.1, &r.2][i].chars().count())
+            .max()
+            .unwrap_or(0)
+    };
+    let (from_w, arrow_w, to_w) = (width(0), width(1), width(2));
+    rows.iter()
+        .map(|(from, arrow, to, note)| {
+            format!("{from:<from_w$}  {arrow:<arrow_w$}  {to:<to_w$}  {note}\n")
+        })
+        .collect()
+}
+
 /// Adds a new task
 fn cmd_add(
     store: &TaskStore,
-    title: &str,
-    description: Option<&str>,
-    thaw_date_spec: Option<&str>,
-    due_date_spec: Option<&str>,
+    args: &AddArgs,
     today: chrono::NaiveDate,
+    config: &Config,
 ) -> Result<()> {
     let mut tasks = store.load()?;
-    let id = TaskStore::next_id(&tasks);
+    let id = store.next_id(&tasks);
+
+    // Only a recognized trailing phrase is taken; any other title is kept as typed
+    let extracted = args
+        .due_from_title
+        .then(|| models::extract_due_phrase(&args.title, today))
+        .flatten();
+    let title = extracted.as_ref().map_or(&args.title, |(title, _)| title);
+
+    if let Some(existing) = models::find_duplicate_title(&tasks, title) {
+        if args.unique {
+            bail!(
+                "Task {} already has this title: {} (drop --unique to add it anyway)",
+                existing.id,
+                existing.title
+            );
+        }
+        if config.defaults.warn_duplicates {
+            println!(
+                "Note: a task with a similar title already exists (#{})",
+                existing.id
+            );
+        }
+    }
 
-    let (task_state, thaw_date) = match thaw_date_spec {
+    let mut depends_on = args.depends_on.clone();
+    if let Some(after) = args.after
+        && !depends_on.contains(&after)
+    {
+        depends_on.push(after);
+    }
+    for &dep in &depends_on {
+        deps::check_dependency(&tasks, id, dep)?;
+    }
+
+    let (task_state, thaw_date) = match args.thaw_date.as_deref() {
         Some(spec) => {
             let date = parse_date_spec(spec, today)?;
             (TaskState::Iced, Some(date))
@@ -100,20 +540,48 @@ fn cmd_add(
         None => (TaskState::Melted, None),
     };
 
-    let due_date = match due_date_spec {
+    let due_date = match args.due_date.as_deref() {
         Some(spec) => Some(parse_date_spec(spec, today)?),
-        None => None,
+        None if extracted.is_some() => extracted.as_ref().map(|(_, date)| *date),
+        None if args.inherit_due => {
+            if depends_on.is_empty() {
+                bail!("--inherit-due needs a dependency to inherit from (use --depends or --after)");
+            }
+            tasks
+                .iter()
+                .filter(|t| depends_on.contains(&t.id))
+                .filter_map(|t| t.due_date)
+                .min()
+        }
+        None => match &config.defaults.default_due {
+            Some(spec) => Some(parse_date_spec(spec, today)?),
+            None => None,
+        },
     };
 
-    let task = Task {
+    let mut task = Task {
         id,
-        title: title.to_string(),
-        description: description.unwrap_or_default().to_string(),
+        title: title.clone(),
+        description: args.description.clone().unwrap_or_default(),
         state: task_state,
         thaw_date,
         due_date,
         created_at: today,
+        depends_on,
+        previous_state: None,
+        priority: args.priority,
+        tags: Vec::new(),
+        completed_at: None,
+        url: args.url.clone(),
+        estimate: args.estimate.filter(|minutes| *minutes > 0),
+        subtasks: Vec::new(),
+        updated_at: None,
+        burn_pending_until: None,
+        remind: args.remind.clone().filter(|r| !r.is_empty()),
+        thawed_at: None,
+        frozen_at: thaw_date.map(|_| today),
     };
+    add_tags(&mut task, &args.tags);
 
     println!(
         "Added task {} [{}]: {}",
@@ -121,219 +589,1652 @@ fn cmd_add(
         task.state,
         task.title
     );
+    if let Some((_, date)) = &extracted {
+        println!("Due date from the title: {date}");
+    }
 
     tasks.push(task);
     store.save(&tasks)?;
     Ok(())
 }
 
+/// The new value of a date edited with `spec`. A leading "+" or "-" moves the `current` date
+/// ("+3d" is three days after it); anything else is relative to `today` as usual.
+fn edited_date(
+    spec: &str,
+    current: Option<chrono::NaiveDate>,
+    field: &str,
+    today: chrono::NaiveDate,
+) -> Result<chrono::NaiveDate> {
+    let (offset, forward) = match (spec.strip_prefix('+'), spec.strip_prefix('-')) {
+        (Some(offset), _) => (offset, true),
+        (_, Some(offset)) => (offset, false),
+        _ => return parse_date_spec(spec, today),
+    };
+    if !offset.ends_with(['d', 'w']) {
+        bail!("Invalid {field} date '{spec}': a +/- change needs days or weeks (e.g. +3d, -1w)");
+    }
+    let Some(current) = current else {
+        bail!("The task has no {field} date to move by {spec} (set one without the sign)");
+    };
+    if forward {
+        parse_date_spec(offset, current)
+    } else {
+        models::parse_past_date_spec(offset, current)
+    }
+}
+
+/// Adds tags to a task, normalized and without duplicates
+fn add_tags(task: &mut Task, tags: &[String]) {
+    for tag in tags.iter().map(|t| models::normalize_tag(t)) {
+        if !tag.is_empty() && !task.tags.contains(&tag) {
+            task.tags.push(tag);
+        }
+    }
+}
+
 /// Edits an existing task
 fn cmd_edit(
     store: &TaskStore,
-    id: u32,
-    new_title: Option<&str>,
-    new_description: Option<&str>,
-    new_thaw_date: Option<&str>,
-    new_due_date: Option<&str>,
+    args: &EditArgs,
     today: chrono::NaiveDate,
+    config: &Config,
 ) -> Result<()> {
-    let mut tasks = store.load()?;
-    state::auto_warm(&mut tasks, today);
+    let mut tasks = load_and_thaw(store, today)?;
+    let id = args.id.id;
+    // Checked up front so a bad list name leaves the task untouched
+    let target = match &args.move_to {
+        Some(name) => {
+            let target = TaskStore::for_list(config, name)?;
+            if target.path() == store.path() {
+                bail!("Task {id} is already in list '{name}'");
+            }
+            Some((name.as_str(), target))
+        }
+        None => None,
+    };
+
+    for &dep in &args.depends_on {
+        deps::check_dependency(&tasks, id, dep)?;
+    }
 
     let task = tasks
         .iter_mut()
         .find(|t| t.id == id)
         .ok_or_else(|| anyhow::anyhow!("Task {id} not found"))?;
+    let before = task.clone();
 
-    if let Some(title) = new_title {
-        task.title = title.to_string();
+    if let Some(title) = &args.title {
+        task.title = title.clone();
     }
-    if let Some(desc) = new_description {
-        task.description = desc.to_string();
+    if let Some(desc) = &args.description {
+        task.description = desc.clone();
     }
-    if let Some(spec) = new_thaw_date {
-        task.thaw_date = Some(parse_date_spec(spec, today)?);
+    if let Some(spec) = &args.thaw_date {
+        task.thaw_date = Some(edited_date(spec, task.thaw_date, "thaw", today)?);
     }
-    if let Some(spec) = new_due_date {
-        task.due_date = Some(parse_date_spec(spec, today)?);
+    if let Some(spec) = &args.due_date {
+        task.due_date = Some(edited_date(spec, task.due_date, "due", today)?);
     }
-
-    println!(
-        "Updated task {} [{}]: {}",
-        task.id,
-        task.state,
-        task.title
-    );
-
-    store.save(&tasks)?;
-    Ok(())
-}
-
-/// Shows task details
-fn cmd_show(store: &TaskStore, id: u32, today: chrono::NaiveDate) -> Result<()> {
-    let mut tasks = store.load()?;
-    state::auto_warm(&mut tasks, today);
-    store.save(&tasks)?;
-
-    let task = tasks
-        .iter()
-        .find(|t| t.id == id)
-        .ok_or_else(|| anyhow::anyhow!("Task {id} not found"))?;
-
-    println!("{:<14} {}", "ID:".bold(), task.id);
-    println!("{:<14} {}", "Title:".bold(), task.title);
-    if !task.description.is_empty() {
-        println!("{:<14} {}", "Description:".bold(), task.description);
+    if let Some(priority) = args.priority {
+        task.priority = priority;
     }
-    println!("{:<14} {}", "State:".bold(), colored_state(task.state));
-    println!("{:<14} {}", "Thaw Date:".bold(), date_str(task.thaw_date));
-    println!("{:<14} {}", "Due Date:".bold(), date_str(task.due_date));
-    println!("{:<14} {}", "Created:".bold(), task.created_at);
-
-    Ok(())
-}
-
-/// Lists tasks
-/// Column order: ID, Task, State, Thaw Date, Due Date
-fn cmd_list(store: &TaskStore, iced: bool, all: bool, today: chrono::NaiveDate) -> Result<()> {
-    let mut tasks = store.load()?;
-    let warmed = state::auto_warm(&mut tasks, today);
-    if warmed > 0 {
-        store.save(&tasks)?;
+    if let Some(url) = &args.url {
+        task.url = Some(url.clone()).filter(|u| !u.is_empty());
+    }
+    if let Some(remind) = &args.remind {
+        task.remind = Some(remind.clone()).filter(|r| !r.is_empty());
+    }
+    if let Some(minutes) = args.estimate {
+        task.estimate = Some(minutes).filter(|m| *m > 0);
+    }
+    task.subtasks.extend(args.subtasks.iter().map(|title| Subtask {
+        title: title.clone(),
+        done: false,
+    }));
+    for (numbers, done) in [(&args.check, true), (&args.uncheck, false)] {
+        for &n in numbers {
+            let count = task.subtasks.len();
+            let subtask = n
+                .checked_sub(1)
+                .and_then(|i| task.subtasks.get_mut(i))
+                .ok_or_else(|| anyhow::anyhow!("Task {id} has no subtask {n} (it has {count})"))?;
+            subtask.done = done;
+        }
+    }
+    add_tags(task, &args.tags);
+    let untags: Vec<String> = args.untags.iter().map(|t| models::normalize_tag(t)).collect();
+    task.tags.retain(|t| !untags.contains(t));
+    for &dep in &args.depends_on {
+        if !task.depends_on.contains(&dep) {
+            task.depends_on.push(dep);
+        }
     }
 
-    let filtered: Vec<&Task> = if all {
-        tasks.iter().collect()
-    } else if iced {
-        tasks
-            .iter()
-            .filter(|t| t.state == TaskState::Iced)
-            .collect()
-    } else {
-        // Default: Only Melting and Melted tasks
-        tasks
-            .iter()
-            .filter(|t| t.state == TaskState::Melting || t.state == TaskState::Melted)
-            .collect()
-    };
-
-    if filtered.is_empty() {
-        println!("No tasks found.");
+    let changes = task_diff(&before, task);
+    if changes.is_empty() && target.is_none() {
+        println!("No changes to task {}.", task.id);
         return Ok(());
     }
 
-    // Define column widths
-    let id_w = 5;
-    let task_w = filtered
-        .iter()
-        .map(|t| t.title.len())
-        .max()
-        .unwrap_or(4)
-        .max(4); // At least the length of "Task"
-    let state_w = 11; // "Evaporated" = 10 + margin
-    let date_w = 12; // "YYYY-MM-DD" = 10 + margin
-
-    // Header (since bold text includes ANSI codes, padding is manual)
-    println!(
-        "{}  {}  {}  {}  {}",
-        format!("{:<id_w$}", "ID").bold(),
-        format!("{:<task_w$}", "Task").bold(),
-        format!("{:<state_w$}", "State").bold(),
-        format!("{:<date_w$}", "Thaw Date").bold(),
-        "Due Date".bold(),
-    );
-    let total_w = id_w + 2 + task_w + 2 + state_w + 2 + date_w + 2 + date_w;
-    println!("{}", "─".repeat(total_w));
-
-    for task in &filtered {
+    if !changes.is_empty() {
         println!(
-            "{:<id_w$}  {:<task_w$}  {}  {:<date_w$}  {}",
+            "Updated task {} [{}]: {}",
             task.id,
-            task.title,
-            colored_state_padded(task.state, state_w),
-            date_str(task.thaw_date),
-            date_str(task.due_date),
+            task.state,
+            task.title
         );
     }
+    for (field, old, new) in changes {
+        println!("  {field}: {} → {}", old.red(), new.green());
+    }
 
-    Ok(())
+    match target {
+        Some((name, target)) => move_task(store, tasks, id, name, &target, today, config),
+        None => store.save(&tasks),
+    }
 }
 
-/// Melting/Iced -> Melted
-fn cmd_warm(store: &TaskStore, id: u32, today: chrono::NaiveDate) -> Result<()> {
-    let mut tasks = store.load()?;
-    state::auto_warm(&mut tasks, today);
-
-    let task = tasks
-        .iter_mut()
-        .find(|t| t.id == id)
-        .ok_or_else(|| anyhow::anyhow!("Task {id} not found"))?;
+/// Moves task `id` out of `tasks` (the contents of `store`) into the list `name`, under the
+/// next free ID there. IDs only mean something within one list, so dependencies in either
+/// direction are dropped. If the source cannot be saved, the target is put back as it was,
+/// undo snapshot and log included. Each list keeps its own undo history, so `undo` in one of
+/// them does not take the move back in the other.
+fn move_task(
+    store: &TaskStore,
+    mut tasks: Vec<Task>,
+    id: u32,
+    name: &str,
+    target: &TaskStore,
+    today: chrono::NaiveDate,
+    config: &Config,
+) -> Result<()> {
+    let index = tasks.iter().position(|t| t.id == id).expect("task was found by the caller");
+    let mut task = tasks.remove(index);
+    task.depends_on.clear();
+    for other in &mut tasks {
+        other.depends_on.retain(|dep| *dep != id);
+    }
 
-    state::warm(task)?;
-    println!(
-        "Warmed task {} [{}]: {}",
-        task.id, task.state, task.title
-    );
+    let mut target_tasks = load_and_thaw(target, today)?;
+    let checkpoint = target.checkpoint()?;
+    task.id = target.next_id(&target_tasks);
+    let label = match config.list_prefix(name) {
+        Some(prefix) => format!("{prefix}-{}", task.id),
+        None => task.id.to_string(),
+    };
+    let title = task.title.clone();
+    target_tasks.push(task);
+    target.save(&target_tasks)?;
+    if let Err(err) = store.save(&tasks) {
+        // Never leave the task in both lists, nor an undo step for a move that did not happen
+        target.restore(checkpoint)?;
+        return Err(err);
+    }
 
-    store.save(&tasks)?;
+    let from = store.path().file_stem().map_or("".into(), |s| s.to_string_lossy());
+    println!("Moved task {id} from '{from}' to '{name}' as task {label}: {title}");
     Ok(())
 }
 
-/// Melted/Iced -> Evaporated
-fn cmd_burn(store: &TaskStore, id: u32, today: chrono::NaiveDate) -> Result<()> {
-    let mut tasks = store.load()?;
-    state::auto_warm(&mut tasks, today);
-
+/// Permanently removes task `id`; dependencies on it are dropped (`undo` brings it back)
+fn cmd_delete(store: &TaskStore, id: u32, yes: bool, today: chrono::NaiveDate) -> Result<()> {
+    let mut tasks = load_and_thaw(store, today)?;
     let task = tasks
-        .iter_mut()
+        .iter()
         .find(|t| t.id == id)
         .ok_or_else(|| anyhow::anyhow!("Task {id} not found"))?;
+    let title = task.title.clone();
+    if !confirm(&format!("Delete task {id}?"), yes)? {
+        println!("Aborted.");
+        return Ok(());
+    }
 
-    state::burn(task)?;
-    println!(
-        "Burned task {} [{}]: {}",
-        task.id, task.state, task.title
-    );
-
+    tasks.retain(|t| t.id != id);
+    for task in &mut tasks {
+        task.depends_on.retain(|dep| *dep != id);
+    }
     store.save(&tasks)?;
+    println!("Deleted task {id}: {title}");
     Ok(())
 }
 
-/// Evaporated -> Melted
-fn cmd_cool(store: &TaskStore, id: u32, today: chrono::NaiveDate) -> Result<()> {
-    let mut tasks = store.load()?;
-    state::auto_warm(&mut tasks, today);
+/// Lists the fields that differ between two versions of a task as (field, old, new)
+fn task_diff(before: &Task, after: &Task) -> Vec<(&'static str, String, String)> {
+    let quoted = |s: &str| format!("'{s}'");
+    let tags_str = |tags: &[String]| {
+        if tags.is_empty() {
+            "-".to_string()
+        } else {
+            tags.join(", ")
+        }
+    };
+    let deps_str = |deps: &[u32]| {
+        if deps.is_empty() {
+            "-".to_string()
+        } else {
+            deps.iter().map(|d| format!("#{d}")).collect::<Vec<_>>().join(", ")
+        }
+    };
+    let fields = [
+        ("title", quoted(&before.title), quoted(&after.title)),
+        (
+            "description",
+            quoted(&before.description),
+            quoted(&after.description),
+        ),
+        ("state", before.state.to_string(), after.state.to_string()),
+        ("thaw", date_str(before.thaw_date), date_str(after.thaw_date)),
+        ("due", date_str(before.due_date), date_str(after.due_date)),
+        (
+            "priority",
+            before.priority.to_string(),
+            after.priority.to_string(),
+        ),
+        (
+            "depends",
+            deps_str(&before.depends_on),
+            deps_str(&after.depends_on),
+        ),
+        ("tags", tags_str(&before.tags), tags_str(&after.tags)),
+        (
+            "url",
+            before.url.clone().unwrap_or_else(|| "-".to_string()),
+            after.url.clone().unwrap_or_else(|| "-".to_string()),
+        ),
+        (
+            "estimate",
+            before.estimate.map_or_else(|| "-".to_string(), models::format_duration),
+            after.estimate.map_or_else(|| "-".to_string(), models::format_duration),
+        ),
+        (
+            "remind",
+            before.remind.clone().unwrap_or_else(|| "-".to_string()),
+            after.remind.clone().unwrap_or_else(|| "-".to_string()),
+        ),
+        ("subtasks", subtasks_str(before), subtasks_str(after)),
+    ];
+    fields
+        .into_iter()
+        .filter(|(_, old, new)| old != new)
+        .collect()
+}
 
-    let task = tasks
-        .iter_mut()
+/// Checklist progress for diffs, e.g. "1/3 done"
+fn subtasks_str(task: &Task) -> String {
+    if task.subtasks.is_empty() {
+        return "-".to_string();
+    }
+    let total = task.subtasks.len();
+    let done = total - task.open_subtasks();
+    let titles: Vec<&str> = task.subtasks.iter().map(|s| s.title.as_str()).collect();
+    format!("{done}/{total} done ({})", titles.join(", "))
+}
+
+/// Copies a task into a fresh Melted one with a new ID, keeping what describes the work
+fn cmd_clone(
+    store: &TaskStore,
+    id: u32,
+    title: Option<String>,
+    today: chrono::NaiveDate,
+) -> Result<()> {
+    let mut tasks = load_and_thaw(store, today)?;
+    let original = tasks
+        .iter()
         .find(|t| t.id == id)
         .ok_or_else(|| anyhow::anyhow!("Task {id} not found"))?;
 
-    state::cool(task)?;
+    let task = Task {
+        id: store.next_id(&tasks),
+        title: title.unwrap_or_else(|| original.title.clone()),
+        description: original.description.clone(),
+        state: TaskState::Melted,
+        thaw_date: None,
+        due_date: original.due_date,
+        created_at: today,
+        depends_on: Vec::new(),
+        previous_state: None,
+        priority: original.priority,
+        tags: original.tags.clone(),
+        completed_at: None,
+        url: None,
+        estimate: original.estimate,
+        // The copy starts with a fresh checklist
+        subtasks: original
+            .subtasks
+            .iter()
+            .map(|s| Subtask {
+                title: s.title.clone(),
+                done: false,
+            })
+            .collect(),
+        updated_at: None,
+        burn_pending_until: None,
+        remind: original.remind.clone(),
+        thawed_at: None,
+        frozen_at: None,
+    };
     println!(
-        "Cooled task {} [{}]: {}",
+        "Cloned task {id} as {} [{}]: {}",
         task.id, task.state, task.title
     );
 
+    tasks.push(task);
     store.save(&tasks)?;
     Ok(())
 }
 
-/// Any State -> Iced
-fn cmd_freeze(
+/// Merges task `from` into `into`, then deletes `from`. `into` keeps its title, state and thaw
+/// date; descriptions are joined, tags, dependencies and estimates are combined, the earlier
+/// creation day and nearer due date win, and so does the higher priority. A URL is only taken
+/// over when `into` has none, and subtasks are appended. Tasks that depended on `from` depend on
+/// `into` afterwards. Asks first when `defaults.confirm_destructive` is on.
+fn cmd_merge(
     store: &TaskStore,
-    id: u32,
-    thaw_date_spec: Option<&str>,
+    from: u32,
+    into: u32,
+    yes: bool,
     today: chrono::NaiveDate,
     config: &Config,
 ) -> Result<()> {
-    let mut tasks = store.load()?;
-    state::auto_warm(&mut tasks, today);
-
-    let task = tasks
+    if from == into {
+        bail!("Cannot merge task {from} into itself");
+    }
+    let mut tasks = load_and_thaw(store, today)?;
+    let from_index = tasks
+        .iter()
+        .position(|t| t.id == from)
+        .ok_or_else(|| anyhow::anyhow!("Task {from} not found"))?;
+    if !tasks.iter().any(|t| t.id == into) {
+        bail!("Task {into} not found");
+    }
+    if !confirm_destructive(config, yes, &format!("Merge task {from} into {into} and delete it?"))? {
+        println!("Aborted.");
+        return Ok(());
+    }
+    let source = tasks.remove(from_index);
+
+    // References to `from` move to `into`, without duplicates or self-dependencies
+    for task in &mut tasks {
+        for dep in &mut task.depends_on {
+            if *dep == from {
+                *dep = into;
+            }
+        }
+        let id = task.id;
+        let mut seen = HashSet::new();
+        task.depends_on.retain(|d| *d != id && seen.insert(*d));
+    }
+
+    let target = tasks.iter_mut().find(|t| t.id == into).expect("checked above");
+    if !source.description.is_empty() {
+        if target.description.is_empty() {
+            target.description = source.description.clone();
+        } else {
+            target.description = format!("{}\n\n{}", target.description, source.description);
+        }
+    }
+    for tag in &source.tags {
+        if !target.tags.contains(tag) {
+            target.tags.push(tag.clone());
+        }
+    }
+    for dep in source.depends_on.iter().filter(|d| **d != into) {
+        if !target.depends_on.contains(dep) {
+            target.depends_on.push(*dep);
+        }
+    }
+    target.created_at = target.created_at.min(source.created_at);
+    target.due_date = match (target.due_date, source.due_date) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    };
+    target.priority = target.priority.max(source.priority);
+    if target.url.is_none() {
+        target.url = source.url.clone();
+    }
+    target.estimate = match (target.estimate, source.estimate) {
+        (Some(a), Some(b)) => Some(a.saturating_add(b)),
+        (a, b) => a.or(b),
+    };
+    target.subtasks.extend(source.subtasks.iter().cloned());
+
+    let merged_deps = target.depends_on.clone();
+    if let Some(dep) = merged_deps
+        .iter()
+        .find(|dep| deps::depends_transitively(&tasks, **dep, into))
+    {
+        bail!("Cannot merge: task {into} would depend on task {dep}, which depends on it");
+    }
+
+    println!("Merged task {from} into {into}: {}", source.title);
+    store.save(&tasks)?;
+    Ok(())
+}
+
+/// Renumbers task `old` to `new` and rewrites every `depends_on` reference to it
+fn cmd_set_id(store: &TaskStore, old: u32, new: u32, today: chrono::NaiveDate) -> Result<()> {
+    let mut tasks = load_and_thaw(store, today)?;
+    if !tasks.iter().any(|t| t.id == old) {
+        bail!("Task {old} not found");
+    }
+    if new == 0 {
+        bail!("Task IDs start at 1");
+    }
+    if old == new {
+        println!("Task {old} already has that ID.");
+        return Ok(());
+    }
+    if let Some(taken) = tasks.iter().find(|t| t.id == new) {
+        bail!("ID {new} is already taken by: {}", taken.title);
+    }
+
+    let mut references = 0;
+    for task in &mut tasks {
+        if task.id == old {
+            task.id = new;
+        }
+        for dep in task.depends_on.iter_mut().filter(|d| **d == old) {
+            *dep = new;
+            references += 1;
+        }
+    }
+    let noun = if references == 1 { "dependency" } else { "dependencies" };
+    println!("Task {old} is now task {new} ({references} {noun} updated)");
+
+    store.save_renumbered(&tasks, old, new)?;
+    Ok(())
+}
+
+/// Prints the labelled detail lines of `show` for one task
+fn render_task_detail(task: &Task, today: chrono::NaiveDate, weekday_window: u32) {
+    println!("{:<14} {}", "ID:".bold(), task.id);
+    println!("{:<14} {}", "Title:".bold(), task.title);
+    if !task.description.is_empty() {
+        println!("{:<14} {}", "Description:".bold(), task.description);
+    }
+    println!("{:<14} {}", "State:".bold(), colored_state(task.state));
+    println!("{:<14} {}", "Priority:".bold(), task.priority);
+    if !task.tags.is_empty() {
+        println!("{:<14} {}", "Tags:".bold(), task.tags.join(", "));
+    }
+    if let Some(url) = &task.url {
+        println!("{:<14} {}", "URL:".bold(), hyperlink(url));
+    }
+    if let Some(minutes) = task.estimate {
+        println!("{:<14} {}", "Estimate:".bold(), models::format_duration(minutes));
+    }
+    if let Some(remind) = &task.remind {
+        println!("{:<14} {remind} before due", "Remind:".bold());
+    }
+    let with_relative = |date: chrono::NaiveDate| {
+        match models::humanize_date(date, today, weekday_window) {
+            Some(relative) => format!("{date} ({relative})"),
+            None => date.to_string(),
+        }
+    };
+    let thaw_date = match task.thaw_date {
+        Some(date) => with_relative(date),
+        None => date_str(None),
+    };
+    println!("{:<14} {}", "Thaw Date:".bold(), thaw_date);
+    let due_date = match task.due_date {
+        // A finished task is never overdue
+        Some(date) if date < today && task.state != TaskState::Evaporated => {
+            let days = (today - date).num_days();
+            let unit = if days == 1 { "day" } else { "days" };
+            format!("{date} (overdue {days} {unit})")
+        }
+        Some(date) => with_relative(date),
+        None => date_str(None),
+    };
+    println!("{:<14} {}", "Due Date:".bold(), due_date);
+    println!("{:<14} {}", "Created:".bold(), task.created_at);
+    if let Some(until) = task.burn_pending_until {
+        println!("{:<14} until {until} (kelvin unburn {})", "Undo Burn:".bold(), task.id);
+    }
+    if let Some((frozen, active)) = task.frozen_and_active_days() {
+        let days = |n: i64| if n == 1 { "1 day".to_string() } else { format!("{n} days") };
+        println!(
+            "{:<14} frozen for {}, active for {}",
+            "Lifecycle:".bold(),
+            days(frozen),
+            days(active)
+        );
+    }
+    if !task.depends_on.is_empty() {
+        let ids: Vec<String> = task.depends_on.iter().map(|d| format!("#{d}")).collect();
+        println!("{:<14} {}", "Depends On:".bold(), ids.join(", "));
+    }
+    if !task.subtasks.is_empty() {
+        let done = task.subtasks.len() - task.open_subtasks();
+        println!("{:<14} {done}/{} done", "Subtasks:".bold(), task.subtasks.len());
+        for (n, subtask) in task.subtasks.iter().enumerate() {
+            let mark = if subtask.done { "x" } else { " " };
+            println!("  {}. [{mark}] {}", n + 1, subtask.title);
+        }
+    }
+}
+
+/// Shows task details
+fn cmd_show(
+    store: &TaskStore,
+    id: u32,
+    json: bool,
+    plain_dates: bool,
+    history: bool,
+    today: chrono::NaiveDate,
+    config: &Config,
+) -> Result<()> {
+    let tasks = load_and_thaw(store, today)?;
+
+    let task = tasks
+        .iter()
+        .find(|t| t.id == id)
+        .ok_or_else(|| anyhow::anyhow!("Task {id} not found"))?;
+
+    if json {
+        let value = models::tasks_to_json([task], plain_dates)?;
+        print_json(&value[0])?;
+        return Ok(());
+    }
+
+    render_task_detail(task, today, config.defaults.weekday_window);
+
+    if history {
+        println!();
+        println!("{}", "History:".bold());
+        let events = store.task_history(id)?;
+        if events.is_empty() {
+            println!("  (nothing recorded)");
+        }
+        for event in events {
+            println!("  {} {}", event.at.format("%Y-%m-%d"), event.event);
+        }
+    }
+
+    Ok(())
+}
+
+/// Reads one trimmed line from stdin after `prompt`; `None` at end of input
+fn prompt_line(prompt: &str) -> Result<Option<String>> {
+    print!("{prompt}");
+    io::stdout().flush()?;
+    let mut line = String::new();
+    if io::stdin().read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
+    Ok(Some(line.trim().to_string()))
+}
+
+/// Interactive review of the Melting and Melted tasks: each one is shown and the chosen action
+/// applied and saved right away, so quitting half-way keeps what was done (and `undo` works per step).
+fn cmd_triage(store: &TaskStore, today: chrono::NaiveDate, config: &Config) -> Result<()> {
+    if !io::stdin().is_terminal() {
+        bail!("triage is interactive and needs a terminal on stdin");
+    }
+    let mut tasks = load_and_thaw(store, today)?;
+    let queue: Vec<u32> = tasks
+        .iter()
+        .filter(|t| matches!(t.state, TaskState::Melting | TaskState::Melted))
+        .map(|t| t.id)
+        .collect();
+    if queue.is_empty() {
+        println!("Nothing to triage.");
+        return Ok(());
+    }
+
+    for (n, id) in queue.iter().enumerate() {
+        let Some(index) = tasks.iter().position(|t| t.id == *id) else {
+            continue;
+        };
+        println!();
+        println!("{}", format!("── {} of {} ──", n + 1, queue.len()).dimmed());
+        render_task_detail(&tasks[index], today, config.defaults.weekday_window);
+
+        loop {
+            let prompt = "[w]arm, [b]urn, [f]reeze, [e]dit title, [s]kip, [q]uit? ";
+            let Some(answer) = prompt_line(prompt)? else {
+                return Ok(());
+            };
+            let task = &mut tasks[index];
+            let applied = match answer.to_lowercase().as_str() {
+                "w" | "warm" => state::warm(task, today),
+                "b" | "burn" if task.open_subtasks() > 0 => Err(anyhow::anyhow!(
+                    "Task {} still has {} open subtask(s)",
+                    task.id,
+                    task.open_subtasks()
+                )),
+                "b" | "burn" => {
+                    state::burn_with_grace(task, today, config.defaults.burn_grace_days)
+                }
+                "f" | "freeze" => {
+                    let days = config.defaults.thaw_days;
+                    let Some(spec) = prompt_line(&format!("Thaw date [{days}d]: "))? else {
+                        return Ok(());
+                    };
+                    let spec = if spec.is_empty() { format!("{days}d") } else { spec };
+                    parse_date_spec(&spec, today).and_then(|date| state::freeze(task, date, today))
+                }
+                "e" | "edit" => {
+                    let Some(title) = prompt_line("New title (empty keeps it): ")? else {
+                        return Ok(());
+                    };
+                    if !title.is_empty() {
+                        task.title = models::clean_title(&title);
+                    }
+                    Ok(())
+                }
+                "s" | "skip" | "" => break,
+                "q" | "quit" => return Ok(()),
+                other => {
+                    println!("Unknown action: {other}");
+                    continue;
+                }
+            };
+            match applied {
+                Ok(()) => {
+                    println!("Task {} is now {}: {}", task.id, task.state, task.title);
+                    store.save(&tasks)?;
+                    break;
+                }
+                Err(e) => println!("{e}"),
+            }
+        }
+    }
+    Ok(())
+}
+
+/// One stage of the `list` filters: a label for `--explain-filter` and the test a task must pass
+type Filter<'f> = (String, Box<dyn Fn(&Task) -> bool + 'f>);
+
+/// The `list` filters given by the state flags and filter options, in the order they apply.
+/// Options that are not given add no stage.
+fn list_filters(
+    args: &ListArgs,
+    created_on: Option<chrono::NaiveDate>,
+    changed_since: Option<chrono::NaiveDate>,
+    overdue_cutoff: Option<chrono::NaiveDate>,
+    done_on: Option<chrono::NaiveDate>,
+) -> Vec<Filter<'_>> {
+    let mut filters: Vec<Filter> = Vec::new();
+    if args.iced {
+        filters.push(("state iced".into(), Box::new(|t| t.state == TaskState::Iced)));
+    } else if args.melting {
+        filters.push(("state melting".into(), Box::new(|t| t.state == TaskState::Melting)));
+    } else if args.melted {
+        filters.push(("state melted".into(), Box::new(|t| t.state == TaskState::Melted)));
+    } else if !args.all {
+        // Default: Only Melting and Melted tasks, plus those burned on `done_on`
+        filters.push((
+            "state".into(),
+            Box::new(move |t| {
+                t.state == TaskState::Melting
+                    || t.state == TaskState::Melted
+                    || done_on.is_some_and(|day| {
+                        t.state == TaskState::Evaporated && t.completed_at == Some(day)
+                    })
+            }),
+        ));
+    }
+    if let Some((start, end)) = args.id_range {
+        let label = format!("id-range {start}-{end}");
+        filters.push((label, Box::new(move |t| (start..=end).contains(&t.id))));
+    }
+    if let Some(day) = created_on {
+        filters.push((format!("created-on {day}"), Box::new(move |t| t.created_at == day)));
+    }
+    if let Some(day) = changed_since {
+        // Tasks saved before `updated_at` existed count as changed when they were created
+        let changed = move |t: &Task| t.updated_at.unwrap_or(t.created_at) >= day;
+        filters.push((format!("changed-since {day}"), Box::new(changed)));
+    }
+    if args.no_due {
+        filters.push(("no-due".into(), Box::new(|t| t.due_date.is_none())));
+    }
+    if args.has_due {
+        filters.push(("has-due".into(), Box::new(|t| t.due_date.is_some())));
+    }
+    if let Some(min) = args.min_priority {
+        let label = format!("min-priority {}", min.to_string().to_lowercase());
+        filters.push((label, Box::new(move |t| t.priority >= min)));
+    }
+    // Stored tags are normalized, so normalizing the filter makes it case-insensitive
+    for tag in args.tags.iter().map(|tag| models::normalize_tag(tag)) {
+        filters.push((format!("tag {tag}"), Box::new(move |t| t.tags.contains(&tag))));
+    }
+    for tag in args.not_tags.iter().map(|tag| models::normalize_tag(tag)) {
+        filters.push((format!("not-tag {tag}"), Box::new(move |t| !t.tags.contains(&tag))));
+    }
+    if let Some(text) = &args.contains {
+        let needle = text.to_lowercase();
+        let contains = move |t: &Task| {
+            t.title.to_lowercase().contains(&needle)
+                || t.description.to_lowercase().contains(&needle)
+        };
+        filters.push((format!("contains {text}"), Box::new(contains)));
+    }
+    if let (Some(days), Some(cutoff)) = (args.overdue_by, overdue_cutoff) {
+        // Finished tasks are never overdue, even with --all
+        let overdue = move |t: &Task| {
+            t.state != TaskState::Evaporated && t.due_date.is_some_and(|due| due <= cutoff)
+        };
+        filters.push((format!("overdue-by {days}d"), Box::new(overdue)));
+    }
+    filters
+}
+
+/// Selects the tasks that pass every filter, with the number left after each stage
+fn select_tasks<'a>(tasks: &'a [Task], filters: &[Filter]) -> (Vec<&'a Task>, Vec<usize>) {
+    let mut selected: Vec<&Task> = tasks.iter().collect();
+    let mut remaining = Vec::with_capacity(filters.len());
+    for (_, keep) in filters {
+        selected.retain(|t| keep(t));
+        remaining.push(selected.len());
+    }
+    (selected, remaining)
+}
+
+/// A list read by `list --all-lists`, with the ID prefix configured for it
+struct ListSource {
+    name: String,
+    prefix: Option<String>,
+}
+
+/// A row of the task table: the task and, when aggregating lists, the list it came from
+type Row<'a> = (Option<&'a ListSource>, &'a Task);
+
+/// The ID as shown in the table: "W-3" for tasks of a list with a prefix, else "3"
+fn id_label((list, task): &Row) -> String {
+    match list.and_then(|l| l.prefix.as_deref()) {
+        Some(prefix) => format!("{prefix}-{}", task.id),
+        None => task.id.to_string(),
+    }
+}
+
+/// Puts rows into display order. Every view orders its tasks through here,
+/// so flags such as `--reverse` behave the same everywhere.
+/// Without sort keys the stored order is kept; with several, each later key breaks ties
+/// of the ones before it, and the ID breaks the ties that remain.
+/// The `random` key orders rows by a shuffled rank, drawn from `seed` when given.
+fn sort_rows(rows: &mut [Row], sort: &[SortSpec], reverse: bool, seed: Option<u64>) {
+    if !sort.is_empty() {
+        let mut ranks: Vec<usize> = (0..rows.len()).collect();
+        if sort.iter().any(|spec| spec.key == SortKey::Random) {
+            ranks.shuffle(&mut rng(seed));
+        }
+        let mut ranked: Vec<(usize, Row)> = ranks.into_iter().zip(rows.iter().copied()).collect();
+        // Stable sort: rows with the same ID (from different lists) keep the stored order
+        ranked.sort_by(|(rank_a, (_, a)), (rank_b, (_, b))| {
+            sort.iter()
+                .map(|&spec| match spec.key {
+                    SortKey::Random if spec.descending => rank_b.cmp(rank_a),
+                    SortKey::Random => rank_a.cmp(rank_b),
+                    _ => compare_by(spec, a, b),
+                })
+                .find(|order| order.is_ne())
+                .unwrap_or_else(|| a.id.cmp(&b.id))
+        });
+        for (row, (_, sorted)) in rows.iter_mut().zip(ranked) {
+            *row = sorted;
+        }
+    }
+    if reverse {
+        rows.reverse();
+    }
+}
+
+/// Compares two tasks by a single sort key. Missing dates sort last in either direction.
+fn compare_by(spec: SortSpec, a: &Task, b: &Task) -> std::cmp::Ordering {
+    let directed = |order: std::cmp::Ordering| {
+        if spec.descending {
+            order.reverse()
+        } else {
+            order
+        }
+    };
+    match spec.key {
+        SortKey::Id => directed(a.id.cmp(&b.id)),
+        SortKey::Title => directed(a.title.to_lowercase().cmp(&b.title.to_lowercase())),
+        SortKey::State => directed(a.state.cmp(&b.state)),
+        SortKey::Priority => directed(b.priority.cmp(&a.priority)),
+        SortKey::Thaw => cmp_dates(a.thaw_date, b.thaw_date, directed),
+        SortKey::Due => cmp_dates(a.due_date, b.due_date, directed),
+        SortKey::Created => directed(a.created_at.cmp(&b.created_at)),
+        // Ranked by sort_rows, which knows the shuffled positions
+        SortKey::Random => std::cmp::Ordering::Equal,
+    }
+}
+
+/// A generator seeded with `seed`, or from system entropy
+fn rng(seed: Option<u64>) -> StdRng {
+    match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_os_rng(),
+    }
+}
+
+/// Orders optional dates with `None` last; `directed` only applies to two present dates
+fn cmp_dates(
+    a: Option<chrono::NaiveDate>,
+    b: Option<chrono::NaiveDate>,
+    directed: impl Fn(std::cmp::Ordering) -> std::cmp::Ordering,
+) -> std::cmp::Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => directed(a.cmp(&b)),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => std::cmp::Ordering::Equal,
+    }
+}
+
+/// Columns shown by `list` unless `--fields` is given
+const DEFAULT_FIELDS: &[ListField] = &[
+    ListField::Id,
+    ListField::Title,
+    ListField::State,
+    ListField::Thaw,
+    ListField::Due,
+];
+
+/// A column of the `list` table
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Column {
+    /// The list a row came from (`--all-lists`)
+    List,
+    /// Due-date proximity glyph (`--temp`), relative to the given day
+    Temp(chrono::NaiveDate),
+    /// Days until the thaw date (`--iced`), relative to the given day
+    ThawsIn(chrono::NaiveDate),
+    Field(ListField),
+}
+
+impl Column {
+    fn header(self) -> &'static str {
+        match self {
+            Column::List => "List",
+            Column::Temp(_) => "T",
+            Column::ThawsIn(_) => "Thaws In",
+            Column::Field(ListField::Id) => "ID",
+            Column::Field(ListField::Title) => "Task",
+            Column::Field(ListField::State) => "State",
+            Column::Field(ListField::Thaw) => "Thaw Date",
+            Column::Field(ListField::Due) => "Due Date",
+            Column::Field(ListField::Created) => "Created",
+        }
+    }
+
+    /// Natural width: the widest cell or the header, plus a margin for fixed-width columns
+    fn width(self, rows: &[Row]) -> usize {
+        let content = match self {
+            Column::List => rows.iter().filter_map(|(list, _)| list.map(|l| l.name.width())).max(),
+            Column::Temp(_) => Some(1),
+            Column::ThawsIn(today) => rows
+                .iter()
+                .map(|(_, t)| thaw_countdown(t.thaw_date, today).len())
+                .max(),
+            // Four digits + margin, or wider for long (prefixed) IDs
+            Column::Field(ListField::Id) => {
+                rows.iter().map(|r| id_label(r).len() + 1).max().max(Some(5))
+            }
+            Column::Field(ListField::Title) => {
+                rows.iter().map(|(_, t)| t.title.width()).max()
+            }
+            // "Evaporated" = 10 + margin
+            Column::Field(ListField::State) => Some(11),
+            // "YYYY-MM-DD" = 10 + margin
+            Column::Field(ListField::Thaw | ListField::Due | ListField::Created) => Some(12),
+        };
+        content.unwrap_or(0).max(self.header().len())
+    }
+
+    /// The plain value for `row`: no colors, padding or truncation
+    fn text(self, row: &Row) -> String {
+        let (list, task) = row;
+        match self {
+            Column::List => list.map_or("", |l| l.name.as_str()).to_string(),
+            Column::Temp(today) => {
+                let due_date = task.due_date.filter(|_| task.state != TaskState::Evaporated);
+                temperature_char(due_date, today).to_string()
+            }
+            Column::ThawsIn(today) => thaw_countdown(task.thaw_date, today),
+            Column::Field(ListField::Id) => id_label(row),
+            Column::Field(ListField::Title) => task.title.clone(),
+            Column::Field(ListField::State) => task.state.to_string(),
+            Column::Field(ListField::Thaw) => date_str(task.thaw_date),
+            Column::Field(ListField::Due) => date_str(task.due_date),
+            Column::Field(ListField::Created) => task.created_at.to_string(),
+        }
+    }
+
+    /// The cell for `row`, padded to `width` (the title is truncated to fit)
+    fn cell(self, row: &Row, width: usize) -> String {
+        let (list, task) = row;
+        match self {
+            Column::List => pad(list.map_or("", |l| l.name.as_str()), width),
+            Column::Temp(today) => {
+                // Finished tasks have no urgency
+                let due_date = task.due_date.filter(|_| task.state != TaskState::Evaporated);
+                let glyph = temperature_glyph(due_date, today);
+                format!("{glyph}{}", " ".repeat(width.saturating_sub(1)))
+            }
+            Column::ThawsIn(today) => {
+                format!("{:<width$}", thaw_countdown(task.thaw_date, today))
+            }
+            Column::Field(ListField::Id) => format!("{:<width$}", id_label(row)),
+            Column::Field(ListField::Title) => {
+                let title = pad(&truncate(&task.title, width), width);
+                // A second, quieter signal next to the state colors
+                if task.priority == Priority::High {
+                    title.bold().to_string()
+                } else {
+                    title
+                }
+            }
+            Column::Field(ListField::State) => colored_state_padded(task.state, width),
+            Column::Field(ListField::Thaw) => format!("{:<width$}", date_str(task.thaw_date)),
+            Column::Field(ListField::Due) => format!("{:<width$}", date_str(task.due_date)),
+            Column::Field(ListField::Created) => format!("{:<width$}", task.created_at),
+        }
+    }
+}
+
+/// Highlighting of active tasks last changed (or, if never changed, created) before `cutoff`
+/// (`defaults.stale_days`), the same day `--changed-since` goes by
+#[derive(Debug, Clone, Copy)]
+struct Stale {
+    cutoff: chrono::NaiveDate,
+    style: StaleStyle,
+}
+
+impl Stale {
+    fn from_config(config: &Config, today: chrono::NaiveDate) -> Option<Self> {
+        let days = config.defaults.stale_days?;
+        Some(Self {
+            cutoff: today.checked_sub_days(chrono::Days::new(days.into()))?,
+            style: config.defaults.stale_style,
+        })
+    }
+
+    fn applies(self, task: &Task) -> bool {
+        task.state != TaskState::Evaporated
+            && task.updated_at.unwrap_or(task.created_at) < self.cutoff
+    }
+
+    fn paint(self, cell: String) -> String {
+        match self.style {
+            StaleStyle::Dim => cell.dimmed().to_string(),
+            StaleStyle::Bold => cell.bold().to_string(),
+        }
+    }
+}
+
+/// Countdown to a thaw date for the `--iced` view: "today", "in 3d" or "-"
+fn thaw_countdown(thaw_date: Option<chrono::NaiveDate>, today: chrono::NaiveDate) -> String {
+    match thaw_date.map(|date| (date - today).num_days()) {
+        None => "-".to_string(),
+        Some(days) if days <= 0 => "today".to_string(),
+        Some(days) => format!("in {days}d"),
+    }
+}
+
+/// Prints a header and a rule (unless `header` is false), then one line per row.
+/// With `dim_done`, Evaporated rows are dimmed and struck through.
+fn print_table(
+    columns: &[Column],
+    widths: &[usize],
+    rows: &[Row],
+    header: Option<&DisplayConfig>,
+    stale: Option<Stale>,
+    dim_done: bool,
+) {
+    if let Some(display) = header {
+        print_header(columns, widths, display);
+    }
+
+    for row in rows {
+        if dim_done && row.1.state == TaskState::Evaporated {
+            // Plain cells, so the colors of the state column do not end the row style early
+            let cells: Vec<String> = columns
+                .iter()
+                .zip(widths)
+                .map(|(c, &w)| pad(&truncate(&c.text(row), w), w))
+                .collect();
+            println!("{}", cells.join("  ").trim_end().dimmed().strikethrough());
+            continue;
+        }
+        let stale = stale.filter(|s| s.applies(row.1));
+        let cells: Vec<String> = columns
+            .iter()
+            .zip(widths)
+            .map(|(c, &w)| match stale {
+                Some(stale) if *c == Column::Field(ListField::Title) => stale.paint(c.cell(row, w)),
+                _ => c.cell(row, w),
+            })
+            .collect();
+        println!("{}", cells.join("  ").trim_end());
+    }
+}
+
+/// The column names, styled as configured in `[display]`, and the rule below them
+fn print_header(columns: &[Column], widths: &[usize], display: &DisplayConfig) {
+    // Since bold text includes ANSI codes, padding is manual
+    let cells: Vec<String> = columns
+        .iter()
+        .zip(widths)
+        .enumerate()
+        .map(|(i, (c, &w))| {
+            let w = if i + 1 == columns.len() { 0 } else { w };
+            let cell = format!("{:<w$}", c.header());
+            if display.bold_headers {
+                cell.bold().to_string()
+            } else {
+                cell
+            }
+        })
+        .collect();
+    println!("{}", cells.join("  "));
+    if display.header_rule {
+        let gaps = 2 * (columns.len() - 1);
+        println!("{}", display.rule_char.repeat(widths.iter().sum::<usize>() + gaps));
+    }
+}
+
+/// A Markdown (GitHub-flavored) table of `rows`, for pasting into issues and notes
+fn render_markdown(columns: &[Column], rows: &[Row]) -> String {
+    // A pipe would end the cell, and a line break the row
+    let escape = |s: &str| s.replace('|', "\\|").replace(['\r', '\n'], " ");
+    let line = |cells: Vec<String>| format!("| {} |\n", cells.join(" | "));
+    let mut out = line(columns.iter().map(|c| c.header().to_string()).collect());
+    out += &line(columns.iter().map(|_| "---".to_string()).collect());
+    for row in rows {
+        out += &line(columns.iter().map(|c| escape(&c.text(row))).collect());
+    }
+    out
+}
+
+/// Splits rows into labelled groups, keeping the display order within each group.
+/// A row lands in every group its key function yields (a task with two tags appears twice).
+fn group_rows<'a>(rows: &[Row<'a>], by: GroupBy, sort: GroupSort) -> Vec<(String, Vec<Row<'a>>)> {
+    // Groups are keyed by their natural order (state order, or tag name with "(none)" last)
+    let mut groups: BTreeMap<(u8, String), Vec<Row>> = BTreeMap::new();
+    for row in rows {
+        let keys: Vec<(u8, String)> = match by {
+            GroupBy::State => vec![(row.1.state as u8, row.1.state.to_string())],
+            GroupBy::Tag if row.1.tags.is_empty() => vec![(1, "(none)".to_string())],
+            GroupBy::Tag => row.1.tags.iter().map(|tag| (0, tag.clone())).collect(),
+        };
+        for key in keys {
+            groups.entry(key).or_default().push(*row);
+        }
+    }
+    let mut groups: Vec<(String, Vec<Row>)> =
+        groups.into_iter().map(|((_, label), rows)| (label, rows)).collect();
+    if sort == GroupSort::Count {
+        // Stable, so equally sized groups keep their natural order
+        groups.sort_by_key(|(_, rows)| std::cmp::Reverse(rows.len()));
+    }
+    groups
+}
+
+/// `list --watch`: redraws the list every `secs` seconds until interrupted.
+/// With `--notify`, tasks that thaw between redraws trigger an alert.
+fn cmd_watch(store: &TaskStore, args: &ListArgs, secs: u64, config: &Config) -> Result<()> {
+    if let Some(mode) = args.notify {
+        notify::check_available(mode)?;
+    }
+    loop {
+        // Watching may span midnight
+        let today = config.today()?;
+        // Thaw here rather than in cmd_list to learn which tasks thawed
+        let mut tasks = store.load()?;
+        let thawed = state::auto_warm(&mut tasks, today, store.thaw_to());
+        if !thawed.is_empty() {
+            store.save_untracked(&tasks)?;
+        }
+
+        if io::stdout().is_terminal() {
+            // Clear the screen and move the cursor home
+            print!("\x1b[2J\x1b[H");
+        }
+        println!("{}", format!("Every {secs}s, {today} (Ctrl-C to stop)").dimmed());
+        cmd_list(store, args, today, config)?;
+        if let Some(mode) = args.notify
+            && let Err(err) = notify::thawed(&tasks, &thawed, mode)
+        {
+            eprintln!("Warning: notification failed: {err:#}");
+        }
+        std::thread::sleep(std::time::Duration::from_secs(secs));
+    }
+}
+
+/// Lists tasks
+/// Default column order: (List,) ID, Task, State, Thaw Date, Due Date (`--fields` picks others)
+fn cmd_list(
+    store: &TaskStore,
+    args: &ListArgs,
+    today: chrono::NaiveDate,
+    config: &Config,
+) -> Result<()> {
+    let created_on = args
+        .created_on
+        .as_deref()
+        .map(|spec| parse_date_spec(spec, today))
+        .transpose()?;
+    let changed_since = args
+        .changed_since
+        .as_deref()
+        .map(|spec| models::parse_past_date_spec(spec, today))
+        .transpose()?;
+    let json = args.json || args.json_extended;
+    let default_view = !(args.all || args.iced || args.melting || args.melted);
+    // Today's completions are a reminder for the table; scripts reading --json see the
+    // same active tasks as without the setting
+    let done_today = (config.defaults.show_today_done && default_view && !json).then_some(today);
+    // Overdue means due before today, so even "0d" needs a due date of yesterday or earlier
+    let overdue_cutoff = args
+        .overdue_by
+        .map(|days| today - chrono::Duration::days(i64::from(days.max(1))));
+
+    // Each task list is paired with its source, which is only shown when aggregating lists
+    let lists: Vec<(Option<ListSource>, Vec<Task>)> = if args.all_lists {
+        let mut lists = Vec::new();
+        for (name, mut tasks) in store.load_all_lists()? {
+            // Read-only view: thaws are shown but not saved back to the other files
+            state::auto_warm(&mut tasks, today, store.thaw_to());
+            let prefix = config.list_prefix(&name).map(str::to_string);
+            lists.push((Some(ListSource { name, prefix }), tasks));
+        }
+        lists
+    } else {
+        vec![(None, load_and_thaw(store, today)?)]
+    };
+
+    let filters = list_filters(args, created_on, changed_since, overdue_cutoff, done_today);
+    let mut filtered: Vec<Row> = Vec::new();
+    // Tasks left after each filter stage, summed over the lists
+    let mut remaining = vec![0; filters.len()];
+    for (list, tasks) in &lists {
+        let (selected, counts) = select_tasks(tasks, &filters);
+        filtered.extend(selected.into_iter().map(|t| (list.as_ref(), t)));
+        for (sum, count) in remaining.iter_mut().zip(counts) {
+            *sum += count;
+        }
+    }
+    if args.explain_filter {
+        let total: usize = lists.iter().map(|(_, tasks)| tasks.len()).sum();
+        let mut stages = vec![format!("all: {total}")];
+        stages.extend(
+            filters
+                .iter()
+                .zip(&remaining)
+                .map(|((label, _), count)| format!("{label}: {count}")),
+        );
+        if args.offset > 0 {
+            let count = filtered.len().saturating_sub(args.offset);
+            stages.push(format!("offset {}: {count}", args.offset));
+        }
+        if let Some(limit) = args.limit {
+            let count = filtered.len().saturating_sub(args.offset).min(limit);
+            stages.push(format!("limit {limit}: {count}"));
+        }
+        // On stderr, so --json output stays parseable
+        eprintln!("{}", stages.join(" → "));
+    }
+
+    if filtered.is_empty() && !json {
+        // Only the default (active) view gets the celebration
+        let empty_message = &config.defaults.empty_message;
+        if default_view && !empty_message.is_empty() {
+            println!("{empty_message}");
+        } else {
+            println!("No tasks found.");
+        }
+        return Ok(());
+    }
+
+    // An explicit --sort replaces the configured sort; otherwise --reverse flips the configured direction.
+    // The frozen view is about what comes back next, so it sorts by thaw date unless told otherwise.
+    let (sort, reverse) = if !args.sort.is_empty() {
+        (args.sort.clone(), args.reverse)
+    } else if args.iced {
+        (vec![SortSpec::from(SortKey::Thaw)], args.reverse)
+    } else {
+        (
+            config.defaults.sort.clone(),
+            config.defaults.sort_reverse != args.reverse,
+        )
+    };
+    // Sorting comes before grouping: group_rows keeps this order, so each group is sorted too
+    sort_rows(&mut filtered, &sort, reverse, args.seed);
+    if done_today.is_some() {
+        // Today's completions, the only Evaporated tasks in this view, go below the
+        // active tasks (the sort is stable)
+        filtered.sort_by_key(|(_, t)| t.state == TaskState::Evaporated);
+    }
+
+    let total = filtered.len();
+    filtered.drain(..args.offset.min(total));
+    let mut hidden = 0;
+    if let Some(limit) = args.limit {
+        hidden = filtered.len().saturating_sub(limit);
+        filtered.truncate(limit);
+    }
+
+    if json {
+        let tasks = filtered.iter().map(|(_, t)| *t);
+        let mut value = if args.json_extended {
+            // Blocking is decided within the list a task came from
+            let list_of = |task: &Task| {
+                lists
+                    .iter()
+                    .find(|(_, tasks)| tasks.iter().any(|t| std::ptr::eq(t, task)))
+                    .map_or(&[][..], |(_, tasks)| tasks.as_slice())
+            };
+            models::tasks_to_json_with(tasks, args.plain_dates, |task| {
+                let blocked = deps::is_blocked(list_of(task), task);
+                serde_json::to_value(models::TaskView::new(task, today, blocked))
+            })?
+        } else {
+            models::tasks_to_json(tasks, args.plain_dates)?
+        };
+        // A paginated page carries the total so clients know whether there is more;
+        // otherwise the bare array is kept for existing consumers
+        if args.limit.is_some() || args.offset > 0 {
+            value = serde_json::json!({
+                "total": total,
+                "offset": args.offset,
+                "limit": args.limit,
+                "tasks": value,
+            });
+        }
+        if args.compact {
+            println!("{}", serde_json::to_string(&value)?);
+        } else {
+            print_json(&value)?;
+        }
+        return Ok(());
+    }
+
+    // The List column only appears when aggregating lists
+    let mut columns = Vec::new();
+    if args.all_lists {
+        columns.push(Column::List);
+    }
+    if args.temp {
+        columns.push(Column::Temp(today));
+    }
+    if args.iced {
+        columns.push(Column::ThawsIn(today));
+    }
+    match &args.fields {
+        Some(fields) => columns.extend(fields.iter().map(|&f| Column::Field(f))),
+        None => columns.extend(DEFAULT_FIELDS.iter().map(|&f| Column::Field(f))),
+    }
+    if args.format == ListFormat::Markdown {
+        match args.group_by {
+            None => print!("{}", render_markdown(&columns, &filtered)),
+            Some(by) => {
+                let groups = group_rows(&filtered, by, args.group_sort);
+                for (i, (label, rows)) in groups.iter().enumerate() {
+                    if i > 0 {
+                        println!();
+                    }
+                    println!("### {label} ({})\n", rows.len());
+                    print!("{}", render_markdown(&columns, rows));
+                }
+            }
+        }
+        // On stderr, so what gets pasted is only the table
+        if hidden > 0 {
+            eprintln!("... and {hidden} more");
+        }
+        return Ok(());
+    }
+
+    let mut widths: Vec<usize> = columns.iter().map(|c| c.width(&filtered)).collect();
+
+    // Shrink the title column to fit the target width (--width, or the terminal when attached)
+    let target_w = args
+        .width
+        .or_else(|| terminal_size::terminal_size().map(|(w, _)| w.0 as usize))
+        .filter(|w| *w > 0);
+    let gaps = 2 * (columns.len() - 1);
+    let title_i = columns
+        .iter()
+        .position(|c| *c == Column::Field(ListField::Title));
+    if let (Some(target_w), Some(title_i)) = (target_w, title_i) {
+        let fixed_w: usize = gaps + widths.iter().sum::<usize>() - widths[title_i];
+        widths[title_i] = widths[title_i].min(target_w.saturating_sub(fixed_w).max(4));
+    }
+
+    let stale = Stale::from_config(config, today);
+    let header = (!args.no_header).then_some(&config.display);
+    let dim_done = done_today.is_some();
+    match args.group_by {
+        None => print_table(&columns, &widths, &filtered, header, stale, dim_done),
+        Some(by) => {
+            for (i, (label, rows)) in group_rows(&filtered, by, args.group_sort).iter().enumerate() {
+                if i > 0 {
+                    println!();
+                }
+                println!("{} ({})", label.bold(), rows.len());
+                print_table(&columns, &widths, rows, header, stale, dim_done);
+            }
+        }
+    }
+
+    if hidden > 0 {
+        let hint = if args.all {
+            "raise --limit"
+        } else {
+            "use --all or raise --limit"
+        };
+        println!("... and {hidden} more ({hint})");
+    }
+
+    Ok(())
+}
+
+/// Melting/Iced -> Melted; with `all`, every Iced task (after confirming the count)
+fn cmd_warm(
+    store: &TaskStore,
+    id: Option<u32>,
+    all: bool,
+    yes: bool,
+    today: chrono::NaiveDate,
+) -> Result<()> {
+    let mut tasks = load_and_thaw(store, today)?;
+
+    let Some(id) = id else {
+        debug_assert!(all);
+        let iced = tasks.iter().filter(|t| t.state == TaskState::Iced).count();
+        if iced == 0 {
+            println!("No Iced tasks to thaw.");
+            return Ok(());
+        }
+        let noun = if iced == 1 { "task" } else { "tasks" };
+        if !confirm(&format!("Thaw {iced} Iced {noun}?"), yes)? {
+            println!("Aborted.");
+            return Ok(());
+        }
+        for task in tasks.iter_mut().filter(|t| t.state == TaskState::Iced) {
+            state::warm(task, today)?;
+        }
+        store.save(&tasks)?;
+        println!("Thawed {iced} {noun}.");
+        return Ok(());
+    };
+
+    let task = tasks
+        .iter_mut()
+        .find(|t| t.id == id)
+        .ok_or_else(|| anyhow::anyhow!("Task {id} not found"))?;
+
+    state::warm(task, today)?;
+    println!(
+        "Warmed task {} [{}]: {}",
+        task.id, task.state, task.title
+    );
+
+    store.save(&tasks)?;
+    Ok(())
+}
+
+/// Moves the due date of task `id` back by `by`, counted from the due date or, for an overdue
+/// task, from today. With `same_weekday` the new date keeps the weekday of the old one.
+fn cmd_defer(
+    store: &TaskStore,
+    id: u32,
+    by: &str,
+    same_weekday: bool,
+    today: chrono::NaiveDate,
+) -> Result<()> {
+    let mut tasks = load_and_thaw(store, today)?;
+    let task = tasks
+        .iter_mut()
+        .find(|t| t.id == id)
+        .ok_or_else(|| anyhow::anyhow!("Task {id} not found"))?;
+    let Some(due) = task.due_date else {
+        bail!("Task {id} has no due date to defer (set one with `kelvin edit {id} --due`)");
+    };
+
+    let mut new_due = parse_date_spec(by, due.max(today))?;
+    if new_due < due {
+        bail!("Task {id} is due {due}; defer only moves it later (use `kelvin edit {id} --due`)");
+    }
+    if same_weekday {
+        new_due = models::same_weekday_on_or_after(due, new_due)?;
+    }
+    task.due_date = Some(new_due);
+    println!(
+        "Deferred task {}: due {} ({}, was {due})",
+        task.id,
+        new_due,
+        new_due.format("%a")
+    );
+
+    store.save(&tasks)?;
+    Ok(())
+}
+
+/// Lists the tasks whose reminder has started, soonest due first
+fn cmd_reminders(store: &TaskStore, today: chrono::NaiveDate, weekday_window: u32) -> Result<()> {
+    let tasks = load_and_thaw(store, today)?;
+    let mut reminded: Vec<&Task> = tasks.iter().filter(|t| t.reminder_active(today)).collect();
+    if reminded.is_empty() {
+        println!("No reminders.");
+        return Ok(());
+    }
+    reminded.sort_by_key(|t| (t.due_date, t.id));
+    for task in reminded {
+        // reminder_active implies a due date
+        let due = task.due_date.unwrap_or(today);
+        let when = models::humanize_date(due, today, weekday_window).unwrap_or(due.to_string());
+        println!("{} {}  due {when}", format!("#{}", task.id).bold(), task.title);
+    }
+    Ok(())
+}
+
+/// Tasks that can be worked on right now: Melted and not blocked
+fn actionable(tasks: &[Task]) -> impl Iterator<Item = &Task> {
+    tasks
+        .iter()
+        .filter(|t| t.state == TaskState::Melted && !deps::is_blocked(tasks, t))
+}
+
+/// Prints one actionable task chosen at random, to break decision paralysis
+fn cmd_random(store: &TaskStore, seed: Option<u64>, today: chrono::NaiveDate) -> Result<()> {
+    let tasks = load_and_thaw(store, today)?;
+    let candidates: Vec<&Task> = actionable(&tasks).collect();
+    match candidates.choose(&mut rng(seed)) {
+        Some(task) => println!("{} {}", format!("#{}", task.id).bold(), task.title),
+        None => println!("Nothing to pick from: no Melted tasks are ready."),
+    }
+    Ok(())
+}
+
+/// The task to work on next: the unblocked Melted task with the highest priority,
+/// then the soonest due date (tasks without one last), then the lowest ID
+fn next_task(tasks: &[Task]) -> Option<&Task> {
+    actionable(tasks).min_by_key(|t| (std::cmp::Reverse(t.priority), t.due_date.is_none(), t.due_date, t.id))
+}
+
+/// Burns the next task (after confirmation) and prints the one that follows
+fn cmd_pop(store: &TaskStore, yes: bool, today: chrono::NaiveDate, grace_days: u32) -> Result<()> {
+    let tasks = load_and_thaw(store, today)?;
+    let Some(task) = next_task(&tasks) else {
+        println!("All done!");
+        return Ok(());
+    };
+    if !confirm(&format!("Burn task {}: {}?", task.id, task.title), yes)? {
+        println!("Aborted.");
+        return Ok(());
+    }
+    cmd_burn(store, Some(task.id), yes, false, false, today, grace_days)?;
+
+    match next_task(&store.load()?) {
+        Some(next) => println!("Next: {} {}", format!("#{}", next.id).bold(), next.title),
+        None => println!("All done!"),
+    }
+    Ok(())
+}
+
+/// Melted/Iced -> Evaporated
+/// Either a single task by ID, or every Melted task (`--all-melted`, after confirmation) when
+/// `id` is `None`.
+/// Reports the dependents that are no longer blocked, and with `cascade` starts thawing the Iced ones.
+/// Tasks with open subtasks are refused unless `ignore_subtasks` is set; `yes` only answers the
+/// confirmation prompt.
+/// With `grace_days`, the burns can be taken back with `unburn` for that many days.
+fn cmd_burn(
+    store: &TaskStore,
+    id: Option<u32>,
+    yes: bool,
+    ignore_subtasks: bool,
+    cascade: bool,
+    today: chrono::NaiveDate,
+    grace_days: u32,
+) -> Result<()> {
+    let mut tasks = load_and_thaw(store, today)?;
+
+    let targets: Vec<u32> = match id {
+        Some(id) => {
+            let task = tasks
+                .iter()
+                .find(|t| t.id == id)
+                .ok_or_else(|| anyhow::anyhow!("Task {id} not found"))?;
+            let open = task.open_subtasks();
+            if open > 0 && !ignore_subtasks {
+                let noun = if open == 1 { "subtask" } else { "subtasks" };
+                bail!(
+                    "Task {id} still has {open} open {noun} \
+                     (check them off or use --ignore-subtasks)"
+                );
+            }
+            vec![task.id]
+        }
+        None => {
+            let (unfinished, melted): (Vec<&Task>, Vec<&Task>) = tasks
+                .iter()
+                .filter(|t| t.state == TaskState::Melted)
+                .partition(|t| t.open_subtasks() > 0 && !ignore_subtasks);
+            let melted: Vec<u32> = melted.iter().map(|t| t.id).collect();
+            if !unfinished.is_empty() {
+                let ids: Vec<String> = unfinished.iter().map(|t| format!("#{}", t.id)).collect();
+                println!(
+                    "Skipping tasks with open subtasks: {} (use --ignore-subtasks)",
+                    ids.join(", ")
+                );
+            }
+            if melted.is_empty() {
+                println!("No Melted tasks to burn.");
+                return Ok(());
+            }
+            let noun = if melted.len() == 1 { "task" } else { "tasks" };
+            if !confirm(&format!("Burn {} Melted {noun}?", melted.len()), yes)? {
+                println!("Aborted.");
+                return Ok(());
+            }
+            melted
+        }
+    };
+
+    let mut pending_until = None;
+    for task in tasks.iter_mut().filter(|t| targets.contains(&t.id)) {
+        state::burn_with_grace(task, today, grace_days)?;
+        pending_until = task.burn_pending_until;
+        println!(
+            "Burned task {} [{}]: {}",
+            task.id, task.state, task.title
+        );
+    }
+    if let Some(until) = pending_until {
+        println!("Until {until}, `kelvin unburn <id>` takes a burn back.");
+    }
+
+    let unblocked = deps::unblocked_by(&tasks, &targets);
+    if !unblocked.is_empty() {
+        let ids: Vec<String> = unblocked.iter().map(|id| format!("#{id}")).collect();
+        println!("This unblocks {}", ids.join(", "));
+    }
+    if cascade {
+        for task in tasks
+            .iter_mut()
+            .filter(|t| unblocked.contains(&t.id) && t.state == TaskState::Iced)
+        {
+            state::thaw(task, store.thaw_to(), today)?;
+            println!(
+                "Thawed task {} [{}]: {}",
+                task.id, task.state, task.title
+            );
+        }
+    }
+
+    store.save(&tasks)?;
+    Ok(())
+}
+
+/// Evaporated -> Melted
+fn cmd_cool(store: &TaskStore, id: u32, today: chrono::NaiveDate) -> Result<()> {
+    let mut tasks = load_and_thaw(store, today)?;
+
+    let task = tasks
+        .iter_mut()
+        .find(|t| t.id == id)
+        .ok_or_else(|| anyhow::anyhow!("Task {id} not found"))?;
+
+    state::cool(task)?;
+    println!(
+        "Cooled task {} [{}]: {}",
+        task.id, task.state, task.title
+    );
+
+    store.save(&tasks)?;
+    Ok(())
+}
+
+/// Evaporated -> state before burn, only while the burn's grace period lasts
+fn cmd_unburn(store: &TaskStore, id: u32, today: chrono::NaiveDate) -> Result<()> {
+    let mut tasks = load_and_thaw(store, today)?;
+
+    let task = tasks
         .iter_mut()
         .find(|t| t.id == id)
         .ok_or_else(|| anyhow::anyhow!("Task {id} not found"))?;
+    // load_and_thaw has already made expired burns final
+    if task.burn_pending_until.is_none() {
+        bail!("Task {id} has no burn to take back (use `kelvin cool {id}` to reopen it)");
+    }
+
+    state::cool(task)?;
+    println!(
+        "Unburned task {} [{}]: {}",
+        task.id, task.state, task.title
+    );
+
+    store.save(&tasks)?;
+    Ok(())
+}
+
+/// What `freeze` applies to
+enum FreezeTarget {
+    /// One task, with `cascade` also the active tasks that depend on it
+    Task { id: u32, cascade: bool },
+    /// Every task in this state (after confirmation)
+    State(TaskState),
+}
+
+/// Any State -> Iced
+fn cmd_freeze(
+    store: &TaskStore,
+    target: FreezeTarget,
+    yes: bool,
+    thaw_date_spec: Option<&str>,
+    today: chrono::NaiveDate,
+    config: &Config,
+) -> Result<()> {
+    let mut tasks = load_and_thaw(store, today)?;
 
     let thaw_date = match thaw_date_spec {
         Some(spec) => parse_date_spec(spec, today)?,
@@ -345,12 +2246,534 @@ fn cmd_freeze(
         }
     };
 
-    state::freeze(task, thaw_date)?;
+    let targets: Vec<u32> = match target {
+        FreezeTarget::Task { id, cascade } => {
+            let task = tasks
+                .iter()
+                .find(|t| t.id == id)
+                .ok_or_else(|| anyhow::anyhow!("Task {id} not found"))?;
+            let mut targets = vec![task.id];
+            if cascade {
+                // Dependents that are already Iced or done keep their state
+                let dependents = deps::dependents_transitively(&tasks, id);
+                targets.extend(
+                    tasks
+                        .iter()
+                        .filter(|t| dependents.contains(&t.id))
+                        .filter(|t| matches!(t.state, TaskState::Melting | TaskState::Melted))
+                        .map(|t| t.id),
+                );
+            }
+            targets
+        }
+        FreezeTarget::State(in_state) => {
+            let matching: Vec<u32> = tasks
+                .iter()
+                .filter(|t| t.state == in_state)
+                .map(|t| t.id)
+                .collect();
+            if matching.is_empty() {
+                println!("No {in_state} tasks to freeze.");
+                return Ok(());
+            }
+            let noun = if matching.len() == 1 { "task" } else { "tasks" };
+            let prompt = format!("Freeze {} {in_state} {noun} until {thaw_date}?", matching.len());
+            if !confirm(&prompt, yes)? {
+                println!("Aborted.");
+                return Ok(());
+            }
+            matching
+        }
+    };
+
+    // Refreezing with the same date is a no-op; skip the write
+    let mut dirty = false;
+    for task in tasks.iter_mut().filter(|t| targets.contains(&t.id)) {
+        dirty |= task.state != TaskState::Iced || task.thaw_date != Some(thaw_date);
+        state::freeze(task, thaw_date, today)?;
+        println!(
+            "Froze task {} [{}] until {}: {}",
+            task.id, task.state, thaw_date, task.title
+        );
+    }
+    if targets.len() > 1 {
+        println!("Froze {} tasks.", targets.len());
+    }
+    // Frozen tasks drop out of the default list, which is easy to mistake for data loss
+    if thaw_date > today {
+        println!("Hidden from list until {thaw_date} (use `list --iced` to see frozen tasks)");
+    }
+
+    if dirty {
+        store.save(&tasks)?;
+    }
+    Ok(())
+}
+
+/// Writes every task to stdout in the requested format
+fn cmd_export(
+    store: &TaskStore,
+    format: ExportFormat,
+    plain_dates: bool,
+    today: chrono::NaiveDate,
+) -> Result<()> {
+    if plain_dates && format != ExportFormat::Json {
+        bail!("--plain-dates only applies to the json format");
+    }
+    let tasks = load_and_thaw(store, today)?;
+    match format {
+        ExportFormat::Json => {
+            let value = models::tasks_to_json(&tasks, plain_dates)?;
+            println!("{}", serde_json::to_string_pretty(&value)?)
+        }
+        ExportFormat::Ics => {
+            print!("{}", ics::render_calendar(&tasks, chrono::Utc::now().naive_utc()))
+        }
+    }
+    Ok(())
+}
+
+/// Opens the task's URL with the system handler
+fn cmd_open_url(store: &TaskStore, id: u32, today: chrono::NaiveDate) -> Result<()> {
+    let tasks = load_and_thaw(store, today)?;
+    let task = tasks
+        .iter()
+        .find(|t| t.id == id)
+        .ok_or_else(|| anyhow::anyhow!("Task {id} not found"))?;
+    let url = task
+        .url
+        .as_deref()
+        .ok_or_else(|| anyhow::anyhow!("Task {id} has no URL (set one with `edit {id} --url`)"))?;
+    opener::open(url).with_context(|| format!("Failed to open {url}"))?;
+    println!("Opened {url}");
+    Ok(())
+}
+
+/// Prints task counts per state, or a per-week chart of completions with `by_week`
+fn cmd_stats(store: &TaskStore, by_week: Option<u32>, today: chrono::NaiveDate) -> Result<()> {
+    let tasks = load_and_thaw(store, today)?;
+
+    let Some(weeks) = by_week else {
+        for state in [
+            TaskState::Iced,
+            TaskState::Melting,
+            TaskState::Melted,
+            TaskState::Evaporated,
+        ] {
+            let count = tasks.iter().filter(|t| t.state == state).count();
+            println!("{}  {count}", colored_state_padded(state, 11));
+        }
+        println!("{:<11}  {}", "Total".bold(), tasks.len());
+        return Ok(());
+    };
+
+    // Buckets are ISO weeks (Monday to Sunday), oldest first, ending with the current week
+    let this_monday = today.week(chrono::Weekday::Mon).first_day();
+    let buckets: Vec<(chrono::NaiveDate, usize)> = (0..weeks)
+        .rev()
+        .filter_map(|back| this_monday.checked_sub_days(chrono::Days::new(7 * back as u64)))
+        .map(|monday| {
+            let count = tasks
+                .iter()
+                .filter(|t| t.state == TaskState::Evaporated)
+                .filter_map(|t| t.completed_at)
+                .filter(|done| done.week(chrono::Weekday::Mon).first_day() == monday)
+                .count();
+            (monday, count)
+        })
+        .collect();
+
+    let max_bar = 40;
+    let most = buckets.iter().map(|(_, count)| *count).max().unwrap_or(0).max(1);
+    for (monday, count) in buckets {
+        let week = monday.iso_week();
+        // Scale to the busiest week, but never hide a non-zero week entirely
+        let len = (count * max_bar).div_ceil(most);
+        println!(
+            "{}-W{:02}  {count:>3}  {}",
+            week.year(),
+            week.week(),
+            paint(&"█".repeat(len), Palette::current().melted)
+        );
+    }
+    Ok(())
+}
+
+/// Evaporated tasks whose `completed_at` falls within `from..=to`, with the summed estimate
+fn cmd_report(
+    store: &TaskStore,
+    from: &str,
+    to: Option<&str>,
+    today: chrono::NaiveDate,
+) -> Result<()> {
+    let from = parse_date_spec(from, today)?;
+    let to = to.map(|spec| parse_date_spec(spec, today)).transpose()?.unwrap_or(today);
+    if from > to {
+        bail!("--from ({from}) must not be after --to ({to})");
+    }
+
+    let tasks = load_and_thaw(store, today)?;
+    let mut done: Vec<(chrono::NaiveDate, &Task)> = tasks
+        .iter()
+        .filter(|t| t.state == TaskState::Evaporated)
+        .filter_map(|t| t.completed_at.map(|day| (day, t)))
+        .filter(|(day, _)| (from..=to).contains(day))
+        .collect();
+    if done.is_empty() {
+        println!("No tasks completed between {from} and {to}.");
+        return Ok(());
+    }
+    done.sort_by_key(|(day, t)| (*day, t.id));
+
+    let title_w = done
+        .iter()
+        .map(|(_, t)| t.title.width())
+        .max()
+        .unwrap_or(0)
+        .max(4);
     println!(
-        "Froze task {} [{}] until {}: {}",
-        task.id, task.state, thaw_date, task.title
+        "{}  {}  {}  {}",
+        format!("{:<5}", "ID").bold(),
+        format!("{:<title_w$}", "Task").bold(),
+        format!("{:<10}", "Completed").bold(),
+        "Estimate".bold()
     );
+    println!("{}", "─".repeat(5 + title_w + 10 + 8 + 6));
+    let mut total = 0;
+    let mut unestimated = 0;
+    for (day, task) in &done {
+        let estimate = match task.estimate {
+            Some(minutes) => {
+                total += minutes;
+                models::format_duration(minutes)
+            }
+            None => {
+                unestimated += 1;
+                "-".to_string()
+            }
+        };
+        println!("{:<5}  {}  {day}  {estimate:>8}", task.id, pad(&task.title, title_w));
+    }
 
-    store.save(&tasks)?;
+    let noun = if done.len() == 1 { "task" } else { "tasks" };
+    let mut footer = format!("Total: {} ({} {noun}", models::format_duration(total), done.len());
+    if unestimated > 0 {
+        footer.push_str(&format!(", {unestimated} without estimate"));
+    }
+    footer.push(')');
+    println!("{}", footer.bold());
+    Ok(())
+}
+
+/// Validates config.toml strictly; a missing file is valid (defaults apply)
+fn cmd_config_check() -> Result<()> {
+    let path = Config::config_path()?;
+    if !path.exists() {
+        println!("No config file at {}; using defaults.", path.display());
+        return Ok(());
+    }
+    let content = fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    Config::check_str(&content).with_context(|| format!("Invalid config {}", path.display()))?;
+    println!("{} is valid.", path.display());
+    Ok(())
+}
+
+/// `kelvin config set`: writes `key = value` into the config file, which must stay valid
+fn cmd_config_set(key: &str, value: &str) -> Result<()> {
+    let path = Config::config_path()?;
+    let content = if path.exists() {
+        fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?
+    } else {
+        String::new()
+    };
+    let updated = config::set_value(&content, key, value)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(&path, updated).with_context(|| format!("Failed to write {}", path.display()))?;
+    println!("Set {key} = {value} in {}", path.display());
+    Ok(())
+}
+
+/// Loads the tasks file at `path` and reports every problem found, failing if there is any
+fn cmd_validate(path: &std::path::Path) -> Result<()> {
+    if !path.is_file() {
+        bail!("{} does not exist", path.display());
+    }
+    // The configured timezone is only used for repairs when the config can be read
+    let today = Config::load()
+        .and_then(|config| config.today())
+        .unwrap_or_else(|_| chrono::Local::now().date_naive());
+    let tasks = TaskStore::new_with_path(path.to_path_buf(), today).load()?;
+    let problems = check::problems(&tasks);
+    for problem in &problems {
+        println!("{}: {problem}", path.display());
+    }
+    if !problems.is_empty() {
+        let noun = if problems.len() == 1 { "problem" } else { "problems" };
+        bail!("{} is invalid: {} {noun} found", path.display(), problems.len());
+    }
+    let noun = if tasks.len() == 1 { "task" } else { "tasks" };
+    println!("{} is valid ({} {noun}).", path.display(), tasks.len());
+    Ok(())
+}
+
+/// Reverts the last change, or lists the available undo snapshots (newest first)
+fn cmd_undo(
+    store: &TaskStore,
+    steps: usize,
+    list: bool,
+    yes: bool,
+    config: &Config,
+) -> Result<()> {
+    if list {
+        let ring = store.load_snapshots()?;
+        if ring.is_empty() {
+            println!("Nothing to undo.");
+            return Ok(());
+        }
+        println!(
+            "{}  {}  {}",
+            format!("{:<3}", "#").bold(),
+            format!("{:<19}", "Taken").bold(),
+            "Change".bold()
+        );
+        for (n, snapshot) in ring.iter().rev().enumerate() {
+            println!(
+                "{:<3}  {}  {}",
+                n + 1,
+                snapshot.taken_at.format("%Y-%m-%d %H:%M:%S"),
+                snapshot.summary
+            );
+        }
+        return Ok(());
+    }
+
+    let ring = store.load_snapshots()?;
+    if !ring.is_empty() {
+        // Before asking, so a step count that cannot be undone is not confirmed first
+        storage::check_undo_steps(ring.len(), steps)?;
+    }
+    let prompt = match ring.last() {
+        None => None,
+        Some(latest) if steps == 1 => Some(format!("Undo \"{}\"?", latest.summary)),
+        Some(_) => Some(format!("Undo the last {steps} changes?")),
+    };
+    if let Some(prompt) = prompt
+        && !confirm_destructive(config, yes, &prompt)?
+    {
+        println!("Aborted.");
+        return Ok(());
+    }
+    match store.undo(steps)?.as_slice() {
+        [] => println!("Nothing to undo."),
+        [snapshot] => println!("Undid: {}", snapshot.summary),
+        undone => {
+            println!("Undid {} changes:", undone.len());
+            for snapshot in undone {
+                println!("  {}", snapshot.summary);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Prints the tasks file path, or edits it in $EDITOR.
+/// The editor works on a copy; it only replaces the store once it parses as a valid task list.
+fn cmd_open(store: &TaskStore, print: bool, config: &Config) -> Result<()> {
+    let path = std::path::absolute(store.path())?;
+    if print {
+        println!("{}", path.display());
+        return Ok(());
+    }
+    if config.storage.backend != StorageBackend::Json {
+        anyhow::bail!("Editing in $EDITOR is only supported with the json storage backend");
+    }
+
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+    let mut parts = editor.split_whitespace();
+    let program = parts
+        .next()
+        .ok_or_else(|| anyhow::anyhow!("$EDITOR is empty"))?;
+
+    let original = if path.exists() {
+        fs::read_to_string(&path)?
+    } else {
+        "[]\n".to_string()
+    };
+    let mut edit_path = path.clone().into_os_string();
+    edit_path.push(".edit");
+    let edit_path = std::path::PathBuf::from(edit_path);
+    if let Some(parent) = edit_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&edit_path, &original)?;
+
+    let status = std::process::Command::new(program)
+        .args(parts)
+        .arg(&edit_path)
+        .status()
+        .with_context(|| format!("Failed to launch editor '{editor}'"))?;
+    if !status.success() {
+        anyhow::bail!(
+            "Editor exited with {status}; the store was not changed (edits kept in {})",
+            edit_path.display()
+        );
+    }
+
+    let edited = fs::read_to_string(&edit_path)?;
+    if edited == original {
+        fs::remove_file(&edit_path)?;
+        println!("No changes made.");
+        return Ok(());
+    }
+    match JsonStore::parse(&edited, config.today()?) {
+        Ok(tasks) => {
+            // Saving through the store keeps the hand edit undoable
+            store.save(&tasks)?;
+            fs::remove_file(&edit_path)?;
+            println!("Saved {} ({} tasks).", path.display(), tasks.len());
+            Ok(())
+        }
+        Err(e) => anyhow::bail!(
+            "Edited file is not a valid task list: {e}\nThe store was not changed; your edits were kept in {}",
+            edit_path.display()
+        ),
+    }
+}
+
+/// Shows the dependency graph as an indented tree, or as Graphviz DOT with `--dot`.
+/// Each task is followed by the tasks it depends on.
+fn cmd_graph(
+    store: &TaskStore,
+    id: Option<u32>,
+    dot: bool,
+    depth: Option<usize>,
+    today: chrono::NaiveDate,
+) -> Result<()> {
+    let tasks = load_and_thaw(store, today)?;
+    let max_depth = depth.unwrap_or(usize::MAX);
+
+    // Roots: the requested task, or every task that nothing else depends on
+    let roots: Vec<u32> = match id {
+        Some(id) => {
+            if !tasks.iter().any(|t| t.id == id) {
+                anyhow::bail!("Task {id} not found");
+            }
+            vec![id]
+        }
+        None => tasks
+            .iter()
+            .filter(|t| !t.depends_on.is_empty())
+            .filter(|t| !tasks.iter().any(|o| o.depends_on.contains(&t.id)))
+            .map(|t| t.id)
+            .collect(),
+    };
+
+    let mut walk = GraphWalk {
+        tasks: &tasks,
+        max_depth,
+        path: Vec::new(),
+        visited: HashSet::new(),
+        lines: Vec::new(),
+        edges: Vec::new(),
+    };
+    for root in roots {
+        walk.visit(root, 0);
+    }
+    // Tasks caught only in a cycle have no root; walk them too so they are not silently dropped
+    if id.is_none() {
+        for task in tasks.iter().filter(|t| !t.depends_on.is_empty()) {
+            if !walk.visited.contains(&task.id) {
+                walk.visit(task.id, 0);
+            }
+        }
+    }
+    let GraphWalk {
+        visited,
+        lines,
+        mut edges,
+        ..
+    } = walk;
+
+    if dot {
+        println!("digraph kelvin {{");
+        println!("    node [shape=box];");
+        let mut ids: Vec<u32> = visited.into_iter().collect();
+        ids.sort_unstable();
+        for task in ids.iter().filter_map(|id| tasks.iter().find(|t| t.id == *id)) {
+            let label = format!("#{} {}\\n({})", task.id, dot_escape(&task.title), task.state);
+            println!("    \"{}\" [label=\"{label}\"];", task.id);
+        }
+        edges.sort_unstable();
+        edges.dedup();
+        for (from, to) in edges {
+            println!("    \"{from}\" -> \"{to}\";");
+        }
+        println!("}}");
+        return Ok(());
+    }
+
+    if lines.is_empty() {
+        println!("No dependencies found.");
+        return Ok(());
+    }
+    for line in lines {
+        println!("{line}");
+    }
     Ok(())
 }
+
+/// Depth-first walk used by `cmd_graph`.
+/// `path` holds the IDs on the current branch so cycles are reported instead of followed forever.
+struct GraphWalk<'a> {
+    tasks: &'a [Task],
+    max_depth: usize,
+    path: Vec<u32>,
+    visited: HashSet<u32>,
+    lines: Vec<String>,
+    edges: Vec<(u32, u32)>,
+}
+
+impl GraphWalk<'_> {
+    fn visit(&mut self, id: u32, depth: usize) {
+        let indent = "  ".repeat(depth);
+        let Some(task) = self.tasks.iter().find(|t| t.id == id) else {
+            self.lines.push(format!("{indent}#{id} (missing)"));
+            return;
+        };
+        if self.path.contains(&id) {
+            self.lines
+                .push(format!("{indent}#{} {} (cycle)", task.id, task.title));
+            return;
+        }
+        self.visited.insert(id);
+        self.lines.push(format!(
+            "{indent}#{} {} [{}]",
+            task.id,
+            task.title,
+            colored_state(task.state)
+        ));
+        if task.depends_on.is_empty() {
+            return;
+        }
+        if depth >= self.max_depth {
+            self.lines.push(format!("{indent}  ..."));
+            return;
+        }
+        self.path.push(id);
+        for &dep in &task.depends_on {
+            self.edges.push((id, dep));
+            self.visit(dep, depth + 1);
+        }
+        self.path.pop();
+    }
+}
+
+/// Escapes a string for use inside a double-quoted DOT label
+fn dot_escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
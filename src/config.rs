@@ -1,16 +1,31 @@
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::PathBuf;
 
-use anyhow::Result;
+use anyhow::{bail, Context, Result};
+use chrono::{Local, NaiveDate, Utc};
+use chrono_tz::Tz;
 use serde::Deserialize;
 
+use crate::cli::{self, SortSpec};
+use crate::models::TaskState;
+
 /// Application configuration
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
 pub struct Config {
     #[serde(default = "Config::default_defaults")]
     pub defaults: DefaultsConfig,
     #[serde(default = "Config::default_storage")]
     pub storage: StorageConfig,
+    /// Per-color overrides on top of `defaults.theme`
+    #[serde(default)]
+    pub colors: ColorsConfig,
+    /// Look of the `list` table header
+    #[serde(default)]
+    pub display: DisplayConfig,
+    /// Settings of the named lists next to the data file, keyed by list name (`[lists.work]`)
+    #[serde(default)]
+    pub lists: BTreeMap<String, ListConfig>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -18,26 +33,382 @@ pub struct DefaultsConfig {
     /// Default number of thaw days when freezing
     #[serde(default = "default_thaw_days")]
     pub thaw_days: u32,
+    /// State Iced tasks move to once their thaw date is reached (`--thaw-to` overrides it)
+    #[serde(default)]
+    pub thaw_to: ThawTarget,
+    /// Due date `add` gives tasks without `--due`, e.g. "1w" (unset leaves them undated)
+    #[serde(default)]
+    pub default_due: Option<String>,
+    /// Have the default `list` view end with the tasks burned today, dimmed and struck through
+    #[serde(default)]
+    pub show_today_done: bool,
+    /// Message shown by `list` when there is nothing to work on (empty string falls back to "No tasks found.")
+    #[serde(default = "default_empty_message")]
+    pub empty_message: String,
+    /// Number of undo snapshots to keep (0 disables undo)
+    #[serde(default = "default_undo_depth")]
+    pub undo_depth: usize,
+    /// Fields `list` sorts by when no `--sort` is given, written like `--sort`
+    /// (empty keeps the stored order)
+    #[serde(default, deserialize_with = "deserialize_sort")]
+    pub sort: Vec<SortSpec>,
+    /// Reverse the configured sort (`--reverse` flips it back)
+    #[serde(default)]
+    pub sort_reverse: bool,
+    /// Template of `kelvin summary`, with placeholders such as {melted} and {overdue}
+    #[serde(default = "default_summary_format")]
+    pub summary_format: String,
+    /// Have `add` point out an active task with the same title
+    #[serde(default)]
+    pub warn_duplicates: bool,
+    /// Ask before destructive commands such as `undo` (bulk `burn`/`freeze` always ask)
+    #[serde(default)]
+    pub confirm_destructive: bool,
+    /// Days during which a burn can be taken back with `unburn` (0 makes burns final at once)
+    #[serde(default)]
+    pub burn_grace_days: u32,
+    /// Delete tasks that have been Evaporated for this many days (unset keeps them forever)
+    #[serde(default)]
+    pub auto_purge_days: Option<u32>,
+    /// Warn on stderr when more tasks than this are stored (0 disables the warning)
+    #[serde(default = "default_large_store_warn")]
+    pub large_store_warn: usize,
+    /// `show` names the weekday ("Tue", "next Mon") for dates up to this many days ahead (0 disables it)
+    #[serde(default)]
+    pub weekday_window: u32,
+    /// Preset color palette
+    #[serde(default)]
+    pub theme: Theme,
+    /// `list` styles the titles of active tasks unchanged for more than this many days
+    /// (unset disables it)
+    #[serde(default)]
+    pub stale_days: Option<u32>,
+    /// How stale titles are styled
+    #[serde(default)]
+    pub stale_style: StaleStyle,
+    /// IANA time zone (e.g. "Europe/Berlin") that decides what "today" is (unset uses the system zone)
+    #[serde(default)]
+    pub timezone: Option<String>,
+}
+
+impl Default for DefaultsConfig {
+    fn default() -> Self {
+        Self {
+            thaw_days: default_thaw_days(),
+            thaw_to: ThawTarget::default(),
+            default_due: None,
+            show_today_done: false,
+            empty_message: default_empty_message(),
+            undo_depth: default_undo_depth(),
+            sort: Vec::new(),
+            sort_reverse: false,
+            summary_format: default_summary_format(),
+            warn_duplicates: false,
+            confirm_destructive: false,
+            burn_grace_days: 0,
+            auto_purge_days: None,
+            large_store_warn: default_large_store_warn(),
+            weekday_window: 0,
+            theme: Theme::default(),
+            stale_days: None,
+            stale_style: StaleStyle::default(),
+            timezone: None,
+        }
+    }
+}
+
+/// Preset palettes for states and due-date urgency
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    /// Icy blues for dark terminals
+    #[default]
+    Dark,
+    /// Deeper blues that stay readable on a light background
+    Light,
+    /// No colors (bold and dim only)
+    Mono,
+}
+
+/// Where the automatic thaw puts tasks
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum ThawTarget {
+    /// Thawing, waiting to be warmed
+    #[default]
+    Melting,
+    /// Straight to ready
+    Melted,
+}
+
+impl ThawTarget {
+    pub fn state(self) -> TaskState {
+        match self {
+            ThawTarget::Melting => TaskState::Melting,
+            ThawTarget::Melted => TaskState::Melted,
+        }
+    }
+}
+
+/// Style of stale task titles in `list`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StaleStyle {
+    /// Let old tasks recede
+    #[default]
+    Dim,
+    /// Make old tasks stand out
+    Bold,
+}
+
+/// `[colors]`: "#RRGGBB" values replacing single colors of the theme
+#[derive(Debug, Default, Deserialize)]
+pub struct ColorsConfig {
+    pub iced: Option<String>,
+    pub melting: Option<String>,
+    pub melted: Option<String>,
+    pub evaporated: Option<String>,
+    /// Overdue due dates
+    pub overdue: Option<String>,
+    /// Due within three days
+    pub near_due: Option<String>,
+}
+
+impl ColorsConfig {
+    fn entries(&self) -> [(&'static str, &Option<String>); 6] {
+        [
+            ("iced", &self.iced),
+            ("melting", &self.melting),
+            ("melted", &self.melted),
+            ("evaporated", &self.evaporated),
+            ("overdue", &self.overdue),
+            ("near_due", &self.near_due),
+        ]
+    }
 }
 
+/// `[display]`: the header above the `list` table
 #[derive(Debug, Deserialize)]
+pub struct DisplayConfig {
+    /// Print the column names in bold
+    #[serde(default = "default_true")]
+    pub bold_headers: bool,
+    /// Draw a rule between the column names and the tasks
+    #[serde(default = "default_true")]
+    pub header_rule: bool,
+    /// Single character the rule is drawn with
+    #[serde(default = "default_rule_char")]
+    pub rule_char: String,
+}
+
+impl Default for DisplayConfig {
+    fn default() -> Self {
+        Self {
+            bold_headers: true,
+            header_rule: true,
+            rule_char: default_rule_char(),
+        }
+    }
+}
+
+/// Parses a "#RRGGBB" color
+pub fn parse_hex_color(s: &str) -> Result<(u8, u8, u8)> {
+    let hex = s.strip_prefix('#').unwrap_or(s);
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        bail!("Invalid color '{s}' (expected #RRGGBB)");
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).unwrap();
+    Ok((channel(0), channel(2), channel(4)))
+}
+
+/// `[lists.<name>]`: settings of one named list. The other keys override the
+/// `[defaults]` of the same name while a command works on this list.
+#[derive(Debug, Default, Deserialize)]
+pub struct ListConfig {
+    /// Shown before task IDs of this list by `list --all-lists` ("W" gives "W-3"),
+    /// and accepted by commands taking an ID to address the list
+    pub prefix: Option<String>,
+    /// Thaw days when freezing a task of this list (`defaults.thaw_days`)
+    pub thaw_days: Option<u32>,
+    /// State this list's Iced tasks move to when they thaw (`defaults.thaw_to`)
+    pub thaw_to: Option<ThawTarget>,
+    /// Due date `add` gives new tasks of this list, e.g. "3d" (`defaults.default_due`)
+    pub default_due: Option<String>,
+    /// Message `list` shows when this list has nothing to work on (`defaults.empty_message`)
+    pub empty_message: Option<String>,
+    /// Days a burn in this list can be taken back with `unburn` (`defaults.burn_grace_days`)
+    pub burn_grace_days: Option<u32>,
+    /// Days after which this list's Evaporated tasks are deleted (`defaults.auto_purge_days`)
+    pub auto_purge_days: Option<u32>,
+}
+
+#[derive(Debug, Default, Deserialize)]
 pub struct StorageConfig {
-    /// Path to tasks.json (defaults to ~/.config/kelvin/tasks.json if not specified)
+    /// Path to the data file (defaults to ~/.config/kelvin/tasks.json, or tasks.db for sqlite)
     #[serde(default)]
     pub data_file: Option<String>,
+    /// Storage backend
+    #[serde(default)]
+    pub backend: StorageBackend,
+    /// Write tasks ordered by ID so a version-controlled file gets minimal diffs
+    #[serde(default)]
+    pub sort_on_save: bool,
+}
+
+/// Where tasks are persisted
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageBackend {
+    /// A single JSON file
+    #[default]
+    Json,
+    /// A SQLite database (requires the `sqlite` feature)
+    Sqlite,
+}
+
+/// Reads `defaults.sort` in the `--sort` syntax, e.g. "priority,due:desc"
+fn deserialize_sort<'de, D>(deserializer: D) -> Result<Vec<SortSpec>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let s = String::deserialize(deserializer)?;
+    cli::parse_sort_list(&s).map_err(serde::de::Error::custom)
+}
+
+/// Largest accepted `thaw_days` (about 100 years, the same limit as relative dates)
+const MAX_THAW_DAYS: u32 = 36_525;
+
+fn check_thaw_days(key: &str, thaw_days: u32) -> Result<()> {
+    if !(1..=MAX_THAW_DAYS).contains(&thaw_days) {
+        bail!("{key} must be between 1 and {MAX_THAW_DAYS} (got {thaw_days})");
+    }
+    Ok(())
+}
+
+fn check_auto_purge_days(key: &str, days: Option<u32>) -> Result<()> {
+    if days == Some(0) {
+        bail!("{key} must be at least 1 (remove it to keep evaporated tasks)");
+    }
+    Ok(())
+}
+
+fn check_default_due(key: &str, spec: Option<&str>) -> Result<()> {
+    if let Some(spec) = spec {
+        crate::models::parse_date_spec(spec, NaiveDate::default()).context(key.to_string())?;
+    }
+    Ok(())
 }
 
 fn default_thaw_days() -> u32 {
     7
 }
 
+fn default_undo_depth() -> usize {
+    10
+}
+
+fn default_large_store_warn() -> usize {
+    5000
+}
+
+fn default_summary_format() -> String {
+    crate::models::DEFAULT_SUMMARY_FORMAT.to_string()
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_rule_char() -> String {
+    "─".to_string()
+}
+
+fn default_empty_message() -> String {
+    "All clear! ❄️".to_string()
+}
+
+/// TOML type of a key that `kelvin config set` can change
+#[derive(Debug, Clone, Copy)]
+enum SettingKind {
+    Integer,
+    Bool,
+    Text,
+}
+
+/// The scalar keys `kelvin config set` accepts
+const SETTABLE_KEYS: &[(&str, SettingKind)] = &[
+    ("defaults.thaw_days", SettingKind::Integer),
+    ("defaults.thaw_to", SettingKind::Text),
+    ("defaults.default_due", SettingKind::Text),
+    ("defaults.show_today_done", SettingKind::Bool),
+    ("defaults.empty_message", SettingKind::Text),
+    ("defaults.undo_depth", SettingKind::Integer),
+    ("defaults.sort", SettingKind::Text),
+    ("defaults.sort_reverse", SettingKind::Bool),
+    ("defaults.summary_format", SettingKind::Text),
+    ("defaults.warn_duplicates", SettingKind::Bool),
+    ("defaults.confirm_destructive", SettingKind::Bool),
+    ("defaults.burn_grace_days", SettingKind::Integer),
+    ("defaults.auto_purge_days", SettingKind::Integer),
+    ("defaults.large_store_warn", SettingKind::Integer),
+    ("defaults.weekday_window", SettingKind::Integer),
+    ("defaults.theme", SettingKind::Text),
+    ("defaults.stale_days", SettingKind::Integer),
+    ("defaults.stale_style", SettingKind::Text),
+    ("defaults.timezone", SettingKind::Text),
+    ("storage.data_file", SettingKind::Text),
+    ("storage.backend", SettingKind::Text),
+    ("storage.sort_on_save", SettingKind::Bool),
+    ("display.bold_headers", SettingKind::Bool),
+    ("display.header_rule", SettingKind::Bool),
+    ("display.rule_char", SettingKind::Text),
+];
+
+/// Returns the config file `content` (empty for none) with `key` set to `value`.
+/// The result must pass `Config::check_str`; other settings are kept, comments are not.
+pub fn set_value(content: &str, key: &str, value: &str) -> Result<String> {
+    let Some(&(_, kind)) = SETTABLE_KEYS.iter().find(|(k, _)| *k == key) else {
+        let keys: Vec<&str> = SETTABLE_KEYS.iter().map(|(k, _)| *k).collect();
+        bail!("Unknown config key '{key}' (settable keys: {})", keys.join(", "));
+    };
+    let parsed = match kind {
+        SettingKind::Integer => toml::Value::Integer(
+            value
+                .parse()
+                .map_err(|_| anyhow::anyhow!("{key} takes a whole number (got '{value}')"))?,
+        ),
+        SettingKind::Bool => toml::Value::Boolean(
+            value
+                .parse()
+                .map_err(|_| anyhow::anyhow!("{key} takes true or false (got '{value}')"))?,
+        ),
+        SettingKind::Text => toml::Value::String(value.to_string()),
+    };
+
+    let mut table: toml::Table =
+        toml::from_str(content).context("The config file is not valid TOML")?;
+    let (section, name) = key.split_once('.').expect("settable keys are qualified");
+    let Some(section) = table
+        .entry(section)
+        .or_insert_with(|| toml::Value::Table(toml::Table::new()))
+        .as_table_mut()
+    else {
+        bail!("[{section}] in the config file is not a table");
+    };
+    section.insert(name.to_string(), parsed);
+
+    let updated = toml::to_string(&table)?;
+    Config::check_str(&updated).with_context(|| format!("Cannot set {key} to '{value}'"))?;
+    Ok(updated)
+}
+
 impl Config {
     fn default_defaults() -> DefaultsConfig {
-        DefaultsConfig { thaw_days: 7 }
+        DefaultsConfig::default()
     }
 
     fn default_storage() -> StorageConfig {
-        StorageConfig { data_file: None }
+        StorageConfig::default()
     }
 
     /// Loads the configuration file. Returns default values if the file does not exist.
@@ -48,9 +419,142 @@ impl Config {
         }
         let content = fs::read_to_string(&path)?;
         let config: Config = toml::from_str(&content)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Strict parse for `kelvin config check`: like `load`, but unknown keys are errors too
+    pub fn check_str(content: &str) -> Result<Self> {
+        let mut unknown = Vec::new();
+        let config: Config =
+            serde_ignored::deserialize(toml::Deserializer::new(content), |path| {
+                unknown.push(path.to_string())
+            })?;
+        if !unknown.is_empty() {
+            bail!("Unknown config key(s): {}", unknown.join(", "));
+        }
+        config.validate()?;
         Ok(config)
     }
 
+    /// Checks values that parse fine but cannot work
+    fn validate(&self) -> Result<()> {
+        check_thaw_days("defaults.thaw_days", self.defaults.thaw_days)?;
+        check_auto_purge_days("defaults.auto_purge_days", self.defaults.auto_purge_days)?;
+        if self.defaults.weekday_window > 7 {
+            bail!(
+                "defaults.weekday_window must be between 0 and 7 (got {})",
+                self.defaults.weekday_window
+            );
+        }
+        check_default_due("defaults.default_due", self.defaults.default_due.as_deref())?;
+        self.timezone()?;
+        crate::models::SummaryCounts::default()
+            .render(&self.defaults.summary_format)
+            .context("defaults.summary_format")?;
+        for (name, value) in self.colors.entries() {
+            if let Some(value) = value {
+                parse_hex_color(value).with_context(|| format!("colors.{name}"))?;
+            }
+        }
+        let rule_char = &self.display.rule_char;
+        if rule_char.chars().count() != 1 {
+            bail!("display.rule_char must be a single character (got '{rule_char}')");
+        }
+        let mut prefixes: Vec<String> = Vec::new();
+        for (name, list) in &self.lists {
+            if let Some(thaw_days) = list.thaw_days {
+                check_thaw_days(&format!("lists.{name}.thaw_days"), thaw_days)?;
+            }
+            check_auto_purge_days(&format!("lists.{name}.auto_purge_days"), list.auto_purge_days)?;
+            check_default_due(&format!("lists.{name}.default_due"), list.default_due.as_deref())?;
+            let Some(prefix) = &list.prefix else { continue };
+            if prefix.is_empty() || !prefix.chars().all(|c| c.is_ascii_alphabetic()) {
+                bail!("lists.{name}.prefix must consist of letters only (got '{prefix}')");
+            }
+            if prefixes.contains(&prefix.to_ascii_uppercase()) {
+                bail!("lists.{name}.prefix '{prefix}' is already used by another list");
+            }
+            prefixes.push(prefix.to_ascii_uppercase());
+        }
+        if self.storage.backend == StorageBackend::Sqlite && !cfg!(feature = "sqlite") {
+            bail!(
+                "The sqlite storage backend is not available in this build (enable the `sqlite` feature)"
+            );
+        }
+        Ok(())
+    }
+
+    /// The configured `defaults.timezone`, if any
+    pub fn timezone(&self) -> Result<Option<Tz>> {
+        self.defaults
+            .timezone
+            .as_deref()
+            .map(|name| {
+                name.parse::<Tz>().map_err(|_| {
+                    anyhow::anyhow!(
+                        "defaults.timezone: unknown time zone '{name}' (expected e.g. Europe/Berlin)"
+                    )
+                })
+            })
+            .transpose()
+    }
+
+    /// The current day: in `defaults.timezone` when set, otherwise in the system's local time zone
+    pub fn today(&self) -> Result<NaiveDate> {
+        Ok(match self.timezone()? {
+            Some(tz) => Utc::now().with_timezone(&tz).date_naive(),
+            None => Local::now().date_naive(),
+        })
+    }
+
+    /// Puts the settings of `[lists.<name>]` in place of the `[defaults]` they override,
+    /// for a command working on the list `name`
+    pub fn select_list(&mut self, name: &str) {
+        let Some(list) = self.lists.get(name) else {
+            return;
+        };
+        let defaults = &mut self.defaults;
+        if let Some(thaw_days) = list.thaw_days {
+            defaults.thaw_days = thaw_days;
+        }
+        if let Some(thaw_to) = list.thaw_to {
+            defaults.thaw_to = thaw_to;
+        }
+        if let Some(default_due) = &list.default_due {
+            defaults.default_due = Some(default_due.clone());
+        }
+        if let Some(empty_message) = &list.empty_message {
+            defaults.empty_message = empty_message.clone();
+        }
+        if let Some(burn_grace_days) = list.burn_grace_days {
+            defaults.burn_grace_days = burn_grace_days;
+        }
+        if let Some(auto_purge_days) = list.auto_purge_days {
+            defaults.auto_purge_days = Some(auto_purge_days);
+        }
+    }
+
+    /// The ID prefix configured for the list `name`
+    pub fn list_prefix(&self, name: &str) -> Option<&str> {
+        self.lists.get(name).and_then(|list| list.prefix.as_deref())
+    }
+
+    /// The name of the list whose prefix is `prefix` (compared ignoring case)
+    pub fn list_for_prefix(&self, prefix: &str) -> Result<&str> {
+        self.lists
+            .iter()
+            .find(|(_, list)| {
+                list.prefix
+                    .as_deref()
+                    .is_some_and(|p| p.eq_ignore_ascii_case(prefix))
+            })
+            .map(|(name, _)| name.as_str())
+            .ok_or_else(|| {
+                anyhow::anyhow!("No list has the ID prefix '{prefix}' (see [lists] in the config)")
+            })
+    }
+
     /// Kelvin's configuration directory (~/.config/kelvin/)
     pub fn kelvin_dir() -> Result<PathBuf> {
         let home = dirs::home_dir()
@@ -58,27 +562,24 @@ impl Config {
         Ok(home.join(".config").join("kelvin"))
     }
 
-    fn config_path() -> Result<PathBuf> {
+    pub fn config_path() -> Result<PathBuf> {
         Ok(Self::kelvin_dir()?.join("config.toml"))
     }
 
-    /// Gets the path to tasks.json (can be overridden in the configuration)
+    /// Gets the path to the data file (can be overridden in the configuration)
     pub fn data_file_path(&self) -> Result<PathBuf> {
         match &self.storage.data_file {
             Some(custom_path) => {
                 let path = PathBuf::from(shellexpand::tilde(custom_path).as_ref());
                 Ok(path)
             }
-            None => Ok(Self::kelvin_dir()?.join("tasks.json")),
-        }
-    }
-}
-
-impl Default for Config {
-    fn default() -> Self {
-        Self {
-            defaults: DefaultsConfig { thaw_days: 7 },
-            storage: StorageConfig { data_file: None },
+            None => {
+                let file_name = match self.storage.backend {
+                    StorageBackend::Json => "tasks.json",
+                    StorageBackend::Sqlite => "tasks.db",
+                };
+                Ok(Self::kelvin_dir()?.join(file_name))
+            }
         }
     }
 }
@@ -86,11 +587,13 @@ impl Default for Config {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::cli::SortKey;
 
     #[test]
     fn default_config_values() {
         let config = Config::default();
         assert_eq!(config.defaults.thaw_days, 7);
+        assert_eq!(config.defaults.thaw_to, ThawTarget::Melting);
         assert!(config.storage.data_file.is_none());
     }
 
@@ -119,13 +622,180 @@ data_file = "/tmp/my_tasks.json"
         assert!(config.storage.data_file.is_none());
     }
 
+    #[test]
+    fn sort_default_and_validation() {
+        let config: Config = toml::from_str("").unwrap();
+        assert!(config.defaults.sort.is_empty());
+        assert!(!config.defaults.sort_reverse);
+
+        let config: Config =
+            toml::from_str("[defaults]\nsort = \"due\"\nsort_reverse = true\n").unwrap();
+        assert_eq!(config.defaults.sort, [SortSpec::from(SortKey::Due)]);
+        assert!(config.defaults.sort_reverse);
+
+        let config: Config = toml::from_str("[defaults]\nsort = \"priority,due:desc\"\n").unwrap();
+        assert_eq!(config.defaults.sort.len(), 2);
+        assert!(config.defaults.sort[1].descending);
+
+        let err = toml::from_str::<Config>("[defaults]\nsort = \"size\"\n").unwrap_err();
+        assert!(err.to_string().contains("Unknown sort key"));
+    }
+
+    #[test]
+    fn set_value_updates_one_key() {
+        let content = "[defaults]\nthaw_days = 3\ntheme = \"light\"\n";
+        let updated = set_value(content, "defaults.thaw_days", "14").unwrap();
+        let config = Config::check_str(&updated).unwrap();
+        assert_eq!(config.defaults.thaw_days, 14);
+        assert_eq!(config.defaults.theme, Theme::Light);
+
+        let updated = set_value("", "storage.data_file", "~/tasks.json").unwrap();
+        assert_eq!(updated, "[storage]\ndata_file = \"~/tasks.json\"\n");
+        let updated = set_value("", "display.header_rule", "false").unwrap();
+        assert!(!Config::check_str(&updated).unwrap().display.header_rule);
+    }
+
+    #[test]
+    fn set_value_rejects_unknown_keys_and_bad_values() {
+        let err = set_value("", "defaults.date_format", "%d.%m.%Y").unwrap_err();
+        assert!(err.to_string().contains("Unknown config key"));
+        assert!(set_value("", "thaw_days", "14").is_err());
+        let err = set_value("", "defaults.thaw_days", "soon").unwrap_err();
+        assert!(err.to_string().contains("whole number"));
+        assert!(set_value("", "defaults.thaw_days", "0").is_err());
+        assert!(set_value("", "defaults.theme", "neon").is_err());
+        assert!(set_value("", "display.bold_headers", "yes").is_err());
+    }
+
+    #[test]
+    fn check_rejects_unknown_keys_and_bad_values() {
+        assert!(Config::check_str("[defaults]\nthaw_days = 3\n").is_ok());
+
+        let err = Config::check_str("[defaults]\nthaw_dayz = 3\n[extra]\n").unwrap_err();
+        assert!(err.to_string().contains("defaults.thaw_dayz"));
+        assert!(err.to_string().contains("extra"));
+
+        let err = Config::check_str("[defaults]\nthaw_days = 0\n").unwrap_err();
+        assert!(err.to_string().contains("thaw_days"));
+        assert!(Config::check_str("[defaults]\nthaw_days = \"soon\"\n").is_err());
+        assert!(Config::check_str("[defaults]\nweekday_window = 7\n").is_ok());
+        assert!(Config::check_str("[defaults]\nweekday_window = 8\n").is_err());
+        assert!(Config::check_str("[defaults]\ndefault_due = \"1w\"\n").is_ok());
+        let err = Config::check_str("[defaults]\ndefault_due = \"soon\"\n").unwrap_err();
+        assert!(format!("{err:#}").contains("defaults.default_due"));
+        assert!(Config::check_str("[display]\nrule_char = \"=\"\n").is_ok());
+        assert!(Config::check_str("[display]\nrule_char = \"==\"\n").is_err());
+        assert!(Config::check_str("[display]\nrule_char = \"\"\n").is_err());
+    }
+
+    #[test]
+    fn empty_message_default_and_override() {
+        let config: Config = toml::from_str("").unwrap();
+        assert_eq!(config.defaults.empty_message, "All clear! ❄️");
+
+        let config: Config = toml::from_str("[defaults]\nempty_message = \"\"").unwrap();
+        assert_eq!(config.defaults.empty_message, "");
+        assert_eq!(config.defaults.thaw_days, 7);
+    }
+
+    #[test]
+    fn theme_and_color_overrides() {
+        let config: Config = toml::from_str("").unwrap();
+        assert_eq!(config.defaults.theme, Theme::Dark);
+
+        let config =
+            Config::check_str("[defaults]\ntheme = \"light\"\n[colors]\nmelted = \"#FF8800\"\n")
+                .unwrap();
+        assert_eq!(config.defaults.theme, Theme::Light);
+        assert_eq!(parse_hex_color(config.colors.melted.as_deref().unwrap()).unwrap(), (255, 136, 0));
+
+        let err = Config::check_str("[colors]\niced = \"blue\"\n").unwrap_err();
+        assert!(format!("{err:#}").contains("colors.iced"));
+        assert!(Config::check_str("[defaults]\ntheme = \"neon\"\n").is_err());
+    }
+
+    #[test]
+    fn stale_settings() {
+        let config: Config = toml::from_str("").unwrap();
+        assert!(config.defaults.stale_days.is_none());
+        assert_eq!(config.defaults.stale_style, StaleStyle::Dim);
+
+        let config: Config =
+            toml::from_str("[defaults]\nstale_days = 14\nstale_style = \"bold\"\n").unwrap();
+        assert_eq!(config.defaults.stale_days, Some(14));
+        assert_eq!(config.defaults.stale_style, StaleStyle::Bold);
+    }
+
+    #[test]
+    fn timezone_is_validated() {
+        let config = Config::check_str("[defaults]\ntimezone = \"Pacific/Kiritimati\"\n").unwrap();
+        assert_eq!(config.timezone().unwrap(), Some(chrono_tz::Pacific::Kiritimati));
+        // UTC+14: never behind UTC
+        assert!(config.today().unwrap() >= Utc::now().date_naive());
+
+        let err = Config::check_str("[defaults]\ntimezone = \"Mars/Olympus\"\n").unwrap_err();
+        assert!(err.to_string().contains("Mars/Olympus"));
+        assert!(Config::default().timezone().unwrap().is_none());
+    }
+
+    #[test]
+    fn list_prefixes() {
+        let config =
+            Config::check_str("[lists.work]\nprefix = \"W\"\n[lists.personal]\nprefix = \"P\"\n")
+                .unwrap();
+        assert_eq!(config.list_prefix("work"), Some("W"));
+        assert_eq!(config.list_prefix("tasks"), None);
+        assert_eq!(config.list_for_prefix("p").unwrap(), "personal");
+        assert!(config.list_for_prefix("X").is_err());
+
+        assert!(Config::check_str("[lists.work]\nprefix = \"W1\"\n").is_err());
+        let err = Config::check_str("[lists.a]\nprefix = \"W\"\n[lists.b]\nprefix = \"w\"\n")
+            .unwrap_err();
+        assert!(err.to_string().contains("already used"));
+    }
+
+    #[test]
+    fn list_overrides_replace_defaults() {
+        let mut config = Config::check_str(
+            "[defaults]\nthaw_days = 3\nburn_grace_days = 1\n\
+             [lists.work]\nprefix = \"W\"\nthaw_days = 14\nthaw_to = \"melted\"\n",
+        )
+        .unwrap();
+        config.select_list("personal");
+        assert_eq!(config.defaults.thaw_days, 3);
+        config.select_list("work");
+        assert_eq!(config.defaults.thaw_days, 14);
+        assert_eq!(config.defaults.thaw_to, ThawTarget::Melted);
+        assert_eq!(config.defaults.burn_grace_days, 1);
+
+        let err = Config::check_str("[lists.work]\nthaw_days = 0\n").unwrap_err();
+        assert!(err.to_string().contains("lists.work.thaw_days"));
+        assert!(Config::check_str("[lists.work]\ndefault_due = \"soon\"\n").is_err());
+        assert!(Config::check_str("[lists.work]\nundo_depth = 3\n").is_err());
+    }
+
+    #[test]
+    fn parse_storage_backend() {
+        let config: Config = toml::from_str("[storage]\nbackend = \"sqlite\"").unwrap();
+        assert_eq!(config.storage.backend, StorageBackend::Sqlite);
+        assert!(config.data_file_path().unwrap().ends_with("tasks.db"));
+
+        let config: Config = toml::from_str("").unwrap();
+        assert_eq!(config.storage.backend, StorageBackend::Json);
+        assert!(toml::from_str::<Config>("[storage]\nbackend = \"csv\"").is_err());
+    }
+
     #[test]
     fn custom_data_file_path() {
         let config = Config {
-            defaults: DefaultsConfig { thaw_days: 7 },
+            defaults: DefaultsConfig::default(),
             storage: StorageConfig {
                 data_file: Some("/tmp/custom.json".to_string()),
+                ..StorageConfig::default()
             },
+            colors: ColorsConfig::default(),
+            display: DisplayConfig::default(),
+            lists: BTreeMap::new(),
         };
         let path = config.data_file_path().unwrap();
         assert_eq!(path, PathBuf::from("/tmp/custom.json"));
@@ -0,0 +1,139 @@
+use std::collections::HashSet;
+
+use anyhow::{bail, Result};
+
+use crate::models::{Task, TaskState};
+
+/// Returns true if `from` (transitively) depends on `target`.
+/// Already visited IDs are skipped, so existing cycles in the data cannot cause an infinite loop.
+pub fn depends_transitively(tasks: &[Task], from: u32, target: u32) -> bool {
+    let mut visited = HashSet::new();
+    let mut stack = vec![from];
+    while let Some(id) = stack.pop() {
+        if id == target {
+            return true;
+        }
+        if !visited.insert(id) {
+            continue;
+        }
+        if let Some(task) = tasks.iter().find(|t| t.id == id) {
+            stack.extend(task.depends_on.iter().copied());
+        }
+    }
+    false
+}
+
+/// Validates that task `id` may depend on `dep`: the task must exist, must not be `id` itself,
+/// and must not already depend on `id` (which would create a cycle).
+pub fn check_dependency(tasks: &[Task], id: u32, dep: u32) -> Result<()> {
+    if dep == id {
+        bail!("Task {id} cannot depend on itself");
+    }
+    if !tasks.iter().any(|t| t.id == dep) {
+        bail!("Task {dep} not found");
+    }
+    if depends_transitively(tasks, dep, id) {
+        bail!("Task {id} cannot depend on task {dep}: this would create a dependency cycle");
+    }
+    Ok(())
+}
+
+/// IDs of the tasks that (transitively) depend on `id`, in stored order
+pub fn dependents_transitively(tasks: &[Task], id: u32) -> Vec<u32> {
+    tasks
+        .iter()
+        .filter(|t| t.id != id && depends_transitively(tasks, t.id, id))
+        .map(|t| t.id)
+        .collect()
+}
+
+/// A task is blocked while any task it depends on is not yet Evaporated.
+/// Dependencies on tasks that no longer exist do not block.
+pub fn is_blocked(tasks: &[Task], task: &Task) -> bool {
+    task.depends_on.iter().any(|dep| {
+        tasks
+            .iter()
+            .any(|t| t.id == *dep && t.state != TaskState::Evaporated)
+    })
+}
+
+/// IDs of unfinished tasks that depend on one of `burned` and are no longer blocked
+pub fn unblocked_by(tasks: &[Task], burned: &[u32]) -> Vec<u32> {
+    tasks
+        .iter()
+        .filter(|t| t.state != TaskState::Evaporated)
+        .filter(|t| t.depends_on.iter().any(|dep| burned.contains(dep)))
+        .filter(|t| !is_blocked(tasks, t))
+        .map(|t| t.id)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task_with_deps(id: u32, depends_on: Vec<u32>) -> Task {
+        Task {
+            id,
+            title: format!("Task {id}"),
+            depends_on,
+            ..Task::default()
+        }
+    }
+
+    #[test]
+    fn transitive_dependency_is_found() {
+        let tasks = vec![
+            task_with_deps(1, vec![]),
+            task_with_deps(2, vec![1]),
+            task_with_deps(3, vec![2]),
+        ];
+        assert!(depends_transitively(&tasks, 3, 1));
+        assert!(!depends_transitively(&tasks, 1, 3));
+    }
+
+    #[test]
+    fn existing_cycle_does_not_loop_forever() {
+        let tasks = vec![task_with_deps(1, vec![2]), task_with_deps(2, vec![1])];
+        assert!(!depends_transitively(&tasks, 1, 3));
+    }
+
+    #[test]
+    fn dependents_are_found_transitively_despite_cycles() {
+        let tasks = vec![
+            task_with_deps(1, vec![]),
+            task_with_deps(2, vec![1]),
+            task_with_deps(3, vec![2, 4]),
+            task_with_deps(4, vec![3]),
+            task_with_deps(5, vec![]),
+        ];
+        assert_eq!(dependents_transitively(&tasks, 1), vec![2, 3, 4]);
+        assert_eq!(dependents_transitively(&tasks, 3), vec![4]);
+        assert!(dependents_transitively(&tasks, 5).is_empty());
+    }
+
+    #[test]
+    fn check_dependency_rejects_self_missing_and_cycles() {
+        let tasks = vec![task_with_deps(1, vec![]), task_with_deps(2, vec![1])];
+        assert!(check_dependency(&tasks, 1, 1).is_err());
+        assert!(check_dependency(&tasks, 1, 9).is_err());
+        assert!(check_dependency(&tasks, 1, 2).is_err());
+        assert!(check_dependency(&tasks, 2, 1).is_ok());
+    }
+
+    #[test]
+    fn unblocked_only_when_every_dependency_is_done() {
+        let mut tasks = vec![
+            task_with_deps(1, vec![]),
+            task_with_deps(2, vec![]),
+            task_with_deps(3, vec![1]),
+            task_with_deps(4, vec![1, 2]),
+        ];
+        tasks[0].state = TaskState::Evaporated;
+        assert_eq!(unblocked_by(&tasks, &[1]), vec![3]);
+        assert!(is_blocked(&tasks, &tasks[3]));
+
+        tasks[1].state = TaskState::Evaporated;
+        assert_eq!(unblocked_by(&tasks, &[2]), vec![4]);
+    }
+}
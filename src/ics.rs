@@ -0,0 +1,135 @@
+use chrono::NaiveDateTime;
+
+use crate::models::{Task, TaskState};
+
+/// Maximum line length in octets before folding (RFC 5545 section 3.1)
+const MAX_LINE_OCTETS: usize = 75;
+
+/// Renders tasks with a due date as an iCalendar document with one all-day VEVENT each.
+/// Tasks without a due date and completed (Evaporated) tasks are skipped.
+/// `stamp` is the creation time written to DTSTAMP (UTC).
+pub fn render_calendar(tasks: &[Task], stamp: NaiveDateTime) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//kelvin//kelvin task manager//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+    ];
+    let dtstamp = stamp.format("%Y%m%dT%H%M%SZ");
+    for task in tasks.iter().filter(|t| t.state != TaskState::Evaporated) {
+        let Some(due) = task.due_date else {
+            continue;
+        };
+        // All-day events end (exclusively) on the following day
+        let end = due.succ_opt().unwrap_or(due);
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:kelvin-task-{}@kelvin", task.id));
+        lines.push(format!("DTSTAMP:{dtstamp}"));
+        lines.push(format!("DTSTART;VALUE=DATE:{}", due.format("%Y%m%d")));
+        lines.push(format!("DTEND;VALUE=DATE:{}", end.format("%Y%m%d")));
+        lines.push(format!("SUMMARY:{}", escape_text(&task.title)));
+        if !task.description.is_empty() {
+            lines.push(format!("DESCRIPTION:{}", escape_text(&task.description)));
+        }
+        lines.push("END:VEVENT".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    lines.iter().map(|line| fold_line(line) + "\r\n").collect()
+}
+
+/// Escapes a TEXT value: backslash, semicolon, comma and newlines
+fn escape_text(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            ';' => out.push_str("\\;"),
+            ',' => out.push_str("\\,"),
+            '\n' => out.push_str("\\n"),
+            '\r' => {}
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Folds a content line into chunks of at most 75 octets joined by CRLF + space,
+/// never splitting a multi-byte UTF-8 character
+fn fold_line(line: &str) -> String {
+    let mut out = String::with_capacity(line.len() + line.len() / MAX_LINE_OCTETS * 3);
+    let mut current = 0;
+    for c in line.chars() {
+        // Continuation lines start with a space, which counts toward the limit
+        let limit = if out.is_empty() { MAX_LINE_OCTETS } else { MAX_LINE_OCTETS - 1 };
+        if current + c.len_utf8() > limit {
+            out.push_str("\r\n ");
+            current = 0;
+        }
+        out.push(c);
+        current += c.len_utf8();
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn task(id: u32, title: &str, due_date: Option<NaiveDate>) -> Task {
+        Task {
+            id,
+            title: title.to_string(),
+            due_date,
+            ..Task::default()
+        }
+    }
+
+    fn stamp() -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 1, 1)
+            .unwrap()
+            .and_hms_opt(9, 30, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn renders_all_day_event_for_due_tasks() {
+        let due = NaiveDate::from_ymd_opt(2026, 3, 1).unwrap();
+        let tasks = vec![task(1, "Ship it", Some(due)), task(2, "No deadline", None)];
+        let ics = render_calendar(&tasks, stamp());
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+        assert!(ics.contains("DTSTART;VALUE=DATE:20260301\r\n"));
+        assert!(ics.contains("DTEND;VALUE=DATE:20260302\r\n"));
+        assert!(ics.contains("DTSTAMP:20260101T093000Z\r\n"));
+        assert!(ics.contains("SUMMARY:Ship it\r\n"));
+        assert!(!ics.contains("No deadline"));
+        assert_eq!(ics.matches("BEGIN:VEVENT").count(), 1);
+    }
+
+    #[test]
+    fn escapes_text_values() {
+        assert_eq!(escape_text("a,b;c\\d\ne"), "a\\,b\\;c\\\\d\\ne");
+    }
+
+    #[test]
+    fn folds_long_lines_at_75_octets() {
+        let line = format!("SUMMARY:{}", "x".repeat(200));
+        let folded = fold_line(&line);
+        for part in folded.split("\r\n") {
+            assert!(part.len() <= 75);
+        }
+        assert_eq!(folded.replace("\r\n ", ""), line);
+    }
+
+    #[test]
+    fn folding_keeps_multibyte_characters_intact() {
+        let line = format!("SUMMARY:{}", "雪".repeat(40));
+        let folded = fold_line(&line);
+        for part in folded.split("\r\n") {
+            assert!(part.len() <= 75);
+        }
+        assert_eq!(folded.replace("\r\n ", ""), line);
+    }
+}
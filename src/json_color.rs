@@ -0,0 +1,123 @@
+use colored::Colorize;
+use serde_json::Value;
+
+/// Pretty-prints `value` like `serde_json::to_string_pretty`, with keys, strings, numbers and
+/// literals in different colors. Only meant for a terminal; piped output stays plain JSON.
+pub fn colorize(value: &Value) -> String {
+    render(value, &|token, text| match token {
+        Token::Key => text.blue().bold().to_string(),
+        Token::String => text.green().to_string(),
+        Token::Number => text.cyan().to_string(),
+        Token::Bool => text.yellow().to_string(),
+        Token::Null => text.dimmed().to_string(),
+    })
+}
+
+/// The kinds of JSON text that get their own color
+#[derive(Clone, Copy)]
+enum Token {
+    Key,
+    String,
+    Number,
+    Bool,
+    Null,
+}
+
+/// Pretty-prints `value`, passing every key and scalar through `paint`
+fn render(value: &Value, paint: &dyn Fn(Token, &str) -> String) -> String {
+    let mut out = String::new();
+    write_value(&mut out, value, 0, paint);
+    out
+}
+
+fn write_value(
+    out: &mut String,
+    value: &Value,
+    depth: usize,
+    paint: &dyn Fn(Token, &str) -> String,
+) {
+    match value {
+        Value::Null => out.push_str(&paint(Token::Null, "null")),
+        Value::Bool(b) => out.push_str(&paint(Token::Bool, &b.to_string())),
+        Value::Number(n) => out.push_str(&paint(Token::Number, &n.to_string())),
+        Value::String(s) => out.push_str(&paint(Token::String, &quote(s))),
+        Value::Array(items) if items.is_empty() => out.push_str("[]"),
+        Value::Object(map) if map.is_empty() => out.push_str("{}"),
+        Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                out.push_str(if i == 0 { "\n" } else { ",\n" });
+                indent(out, depth + 1);
+                write_value(out, item, depth + 1, paint);
+            }
+            out.push('\n');
+            indent(out, depth);
+            out.push(']');
+        }
+        Value::Object(map) => {
+            out.push('{');
+            for (i, (key, item)) in map.iter().enumerate() {
+                out.push_str(if i == 0 { "\n" } else { ",\n" });
+                indent(out, depth + 1);
+                out.push_str(&paint(Token::Key, &quote(key)));
+                out.push_str(": ");
+                write_value(out, item, depth + 1, paint);
+            }
+            out.push('\n');
+            indent(out, depth);
+            out.push('}');
+        }
+    }
+}
+
+fn indent(out: &mut String, depth: usize) {
+    out.push_str(&"  ".repeat(depth));
+}
+
+/// A JSON string literal, escaped the way serde_json writes it
+fn quote(s: &str) -> String {
+    Value::String(s.to_string()).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Drops ANSI color sequences (ESC [ ... m)
+    fn strip_ansi(s: &str) -> String {
+        let mut out = String::new();
+        let mut chars = s.chars();
+        while let Some(c) = chars.next() {
+            if c == '\x1b' {
+                for c in chars.by_ref() {
+                    if c == 'm' {
+                        break;
+                    }
+                }
+            } else {
+                out.push(c);
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn layout_matches_serde_pretty() {
+        let value = serde_json::json!({
+            "id": 3,
+            "title": "Say \"hi\"",
+            "done": false,
+            "due_date": null,
+            "tags": ["a", "b"],
+            "depends_on": [],
+            "extra": {}
+        });
+        // A painter of its own, since `colored` only paints when the process-wide switch allows
+        let colored = render(&value, &|token, text| {
+            let code = if matches!(token, Token::Key) { 34 } else { 32 };
+            format!("\x1b[{code}m{text}\x1b[0m")
+        });
+        assert!(colored.contains("\x1b[34m\"id\""));
+        assert_eq!(strip_ansi(&colored), serde_json::to_string_pretty(&value).unwrap());
+    }
+}
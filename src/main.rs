@@ -1,15 +1,21 @@
+mod check;
 mod cli;
 mod commands;
 mod config;
+mod deps;
+mod ics;
+mod json_color;
 mod models;
+mod notify;
 mod state;
 mod storage;
 
+use std::process::ExitCode;
+
 use anyhow::Result;
 use clap::Parser;
 
-fn main() -> Result<()> {
+fn main() -> Result<ExitCode> {
     let cli = cli::Cli::parse();
-    commands::execute(cli.command)?;
-    Ok(())
+    commands::execute(cli.command, cli.yes, cli.thaw_to, cli.in_list)
 }
@@ -1,9 +1,12 @@
-use chrono::NaiveDate;
+use chrono::{Datelike, NaiveDate, Weekday};
+use clap::ValueEnum;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
-/// Task state (Phase)
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+/// Task state (Phase), ordered from coldest to hottest
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, ValueEnum,
+)]
 #[serde(rename_all = "lowercase")]
 pub enum TaskState {
     Iced,
@@ -24,8 +27,37 @@ impl fmt::Display for TaskState {
     }
 }
 
+/// Task priority, ordered from lowest to highest
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, ValueEnum,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Priority {
+    Low,
+    #[default]
+    Normal,
+    High,
+}
+
+impl Priority {
+    fn is_normal(&self) -> bool {
+        *self == Priority::Normal
+    }
+}
+
+impl fmt::Display for Priority {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Priority::Low => "Low",
+            Priority::Normal => "Normal",
+            Priority::High => "High",
+        };
+        write!(f, "{s}")
+    }
+}
+
 /// Task
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Task {
     pub id: u32,
     pub title: String,
@@ -34,25 +66,460 @@ pub struct Task {
     pub thaw_date: Option<NaiveDate>,
     pub due_date: Option<NaiveDate>,
     pub created_at: NaiveDate,
+    /// IDs of tasks that must be done before this one
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<u32>,
+    /// State before the task was burned, restored by `cool`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous_state: Option<TaskState>,
+    /// How urgent the task is (omitted from the file when Normal)
+    #[serde(default, skip_serializing_if = "Priority::is_normal")]
+    pub priority: Priority,
+    /// Free-form labels, stored lowercase
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Day the task was burned; cleared by `cool`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completed_at: Option<NaiveDate>,
+    /// Link to a related ticket, PR or document
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Estimated effort in minutes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estimate: Option<u32>,
+    /// Checklist of smaller steps; a task with open steps cannot be burned without `--force`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub subtasks: Vec<Subtask>,
+    /// Day of the last change, stamped by the store on save
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<NaiveDate>,
+    /// With `defaults.burn_grace_days`, a burn can be taken back with `unburn` before this day
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub burn_pending_until: Option<NaiveDate>,
+    /// How long before the due date `reminders` starts listing the task (e.g. "2d", "1w")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remind: Option<String>,
+    /// Day the task last left Iced (thawed, warmed or burned while frozen)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thawed_at: Option<NaiveDate>,
+    /// Day the task last entered Iced (added with a thaw date or frozen)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub frozen_at: Option<NaiveDate>,
+}
+
+/// One checklist item of a task
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Subtask {
+    pub title: String,
+    #[serde(default)]
+    pub done: bool,
+}
+
+impl Task {
+    /// Whether the task's reminder has started: it is active, due, and the due date is at
+    /// most `remind` away (overdue tasks stay reminded). Unparsable lead times never fire.
+    pub fn reminder_active(&self, today: NaiveDate) -> bool {
+        let (Some(due), Some(remind)) = (self.due_date, &self.remind) else {
+            return false;
+        };
+        self.state != TaskState::Evaporated
+            && parse_day_span(remind).is_ok_and(|days| (due - today).num_days() <= i64::from(days))
+    }
+
+    /// For a finished task that was Iced at some point: days from when it last froze until
+    /// it thawed, and days from then until it was burned
+    pub fn frozen_and_active_days(&self) -> Option<(i64, i64)> {
+        let (Some(frozen), Some(thawed), Some(completed)) =
+            (self.frozen_at, self.thawed_at, self.completed_at)
+        else {
+            return None;
+        };
+        (self.state == TaskState::Evaporated && frozen <= thawed)
+            .then(|| ((thawed - frozen).num_days(), (completed - thawed).num_days()))
+    }
+
+    /// Number of subtasks not yet checked off
+    pub fn open_subtasks(&self) -> usize {
+        self.subtasks.iter().filter(|s| !s.done).count()
+    }
+}
+
+/// Base of the test fixtures: task 1, a Melted "Test" created on 2026-01-01 with nothing else set.
+/// Tests fill in what they need with `Task { id: 2, ..Task::default() }`.
+#[cfg(test)]
+impl Default for Task {
+    fn default() -> Self {
+        Task {
+            id: 1,
+            title: "Test".to_string(),
+            description: String::new(),
+            state: TaskState::Melted,
+            thaw_date: None,
+            due_date: None,
+            created_at: NaiveDate::from_ymd_opt(2026, 1, 1).unwrap(),
+            depends_on: Vec::new(),
+            previous_state: None,
+            priority: Priority::Normal,
+            tags: Vec::new(),
+            completed_at: None,
+            url: None,
+            estimate: None,
+            subtasks: Vec::new(),
+            updated_at: None,
+            burn_pending_until: None,
+            remind: None,
+            thawed_at: None,
+            frozen_at: None,
+        }
+    }
+}
+
+/// Default `defaults.summary_format` for `kelvin summary`
+pub const DEFAULT_SUMMARY_FORMAT: &str = "🔥{due_today} due today · {melted} melted · {overdue} overdue";
+
+/// The counts `kelvin summary` can show, each available as a `{name}` placeholder
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct SummaryCounts {
+    pub iced: usize,
+    pub melting: usize,
+    pub melted: usize,
+    /// Active tasks due today
+    pub due_today: usize,
+    /// Active tasks past their due date
+    pub overdue: usize,
+    /// Tasks whose reminder lead time has started (see `Task::reminder_active`)
+    pub reminders: usize,
+}
+
+impl SummaryCounts {
+    /// Counts `tasks` as of `today`; Evaporated tasks are never due
+    pub fn of(tasks: &[Task], today: NaiveDate) -> Self {
+        let count = |state| tasks.iter().filter(|t| t.state == state).count();
+        let active_due = || {
+            tasks
+                .iter()
+                .filter(|t| t.state != TaskState::Evaporated)
+                .filter_map(|t| t.due_date)
+        };
+        SummaryCounts {
+            iced: count(TaskState::Iced),
+            melting: count(TaskState::Melting),
+            melted: count(TaskState::Melted),
+            due_today: active_due().filter(|due| *due == today).count(),
+            overdue: active_due().filter(|due| *due < today).count(),
+            reminders: tasks.iter().filter(|t| t.reminder_active(today)).count(),
+        }
+    }
+
+    /// Fills the `{name}` placeholders of `format`. Unknown placeholders are an error.
+    pub fn render(&self, format: &str) -> anyhow::Result<String> {
+        let mut out = format.to_string();
+        for (name, value) in [
+            ("iced", self.iced),
+            ("melting", self.melting),
+            ("melted", self.melted),
+            ("due_today", self.due_today),
+            ("overdue", self.overdue),
+            ("reminders", self.reminders),
+        ] {
+            out = out.replace(&format!("{{{name}}}"), &value.to_string());
+        }
+        if let Some(start) = out.find('{') {
+            let placeholder: String = out[start..].chars().take_while(|&c| c != '}').collect();
+            anyhow::bail!(
+                "Unknown placeholder '{placeholder}}}' \
+                 (expected iced, melting, melted, due_today, overdue or reminders)"
+            );
+        }
+        Ok(out)
+    }
+}
+
+/// Future dates further away than this are only shown as absolute dates
+pub const RELATIVE_DAYS_LIMIT: i64 = 14;
+
+/// Describes `date` relative to `today`: "today", "Tue"/"next Mon" within `weekday_window` days,
+/// "in 5 days" up to two weeks ahead, or "2 days ago". `None` when the date alone says it best.
+pub fn humanize_date(date: NaiveDate, today: NaiveDate, weekday_window: u32) -> Option<String> {
+    let days = (date - today).num_days();
+    let unit = if days.abs() == 1 { "day" } else { "days" };
+    let text = match days {
+        0 => "today".to_string(),
+        d if d > 0 && d <= i64::from(weekday_window) => {
+            if date.iso_week() == today.iso_week() {
+                date.weekday().to_string()
+            } else {
+                format!("next {}", date.weekday())
+            }
+        }
+        d if d > RELATIVE_DAYS_LIMIT => return None,
+        d if d > 0 => format!("in {d} {unit}"),
+        d => format!("{} {unit} ago", -d),
+    };
+    Some(text)
+}
+
+/// Normalizes a tag as entered by the user: trimmed and lowercased, so tags match regardless of case
+pub fn normalize_tag(tag: &str) -> String {
+    tag.trim().to_lowercase()
+}
+
+/// A title as stored: tabs, line breaks and other control characters become spaces,
+/// since they would break the alignment of the `list` table
+pub fn clean_title(title: &str) -> String {
+    title.chars().map(|c| if c.is_control() { ' ' } else { c }).collect()
+}
+
+/// A description as stored: like `clean_title`, but line breaks are kept (as "\n")
+pub fn clean_description(description: &str) -> String {
+    description
+        .replace("\r\n", "\n")
+        .chars()
+        .map(|c| if c.is_control() && c != '\n' { ' ' } else { c })
+        .collect()
+}
+
+/// Normalized form used to compare titles for duplicates: trimmed and lowercased
+pub fn normalize_title(title: &str) -> String {
+    title.trim().to_lowercase()
+}
+
+/// Finds an active (not Evaporated) task whose title matches `title` after normalization
+pub fn find_duplicate_title<'a>(tasks: &'a [Task], title: &str) -> Option<&'a Task> {
+    let wanted = normalize_title(title);
+    tasks
+        .iter()
+        .filter(|t| t.state != TaskState::Evaporated)
+        .find(|t| normalize_title(&t.title) == wanted)
+}
+
+/// Serialized `Task` fields that hold a date. Keep this in sync with `Task`.
+pub const DATE_FIELDS: &[&str] = &[
+    "thaw_date",
+    "due_date",
+    "created_at",
+    "completed_at",
+    "updated_at",
+    "burn_pending_until",
+    "thawed_at",
+    "frozen_at",
+];
+
+/// Day number used by `--plain-dates`: days since the Unix epoch (1970-01-01 is 0)
+pub fn epoch_days(date: NaiveDate) -> i64 {
+    (date - NaiveDate::from_ymd_opt(1970, 1, 1).unwrap()).num_days()
+}
+
+/// Serializes tasks for `--json` output. With `plain_dates`, date fields become `epoch_days` integers.
+pub fn tasks_to_json<'a>(
+    tasks: impl IntoIterator<Item = &'a Task>,
+    plain_dates: bool,
+) -> serde_json::Result<serde_json::Value> {
+    tasks_to_json_with(tasks, plain_dates, serde_json::to_value)
+}
+
+/// Like `tasks_to_json`, serializing each task with `to_value` (e.g. as a `TaskView`)
+pub fn tasks_to_json_with<'a>(
+    tasks: impl IntoIterator<Item = &'a Task>,
+    plain_dates: bool,
+    to_value: impl Fn(&'a Task) -> serde_json::Result<serde_json::Value>,
+) -> serde_json::Result<serde_json::Value> {
+    let mut values = Vec::new();
+    for task in tasks {
+        let mut value = to_value(task)?;
+        if plain_dates {
+            plain_date_fields(&mut value);
+        }
+        values.push(value);
+    }
+    Ok(serde_json::Value::Array(values))
 }
 
-/// Parses a date specification string, either relative ("3d", "1w") or absolute ("2026-03-01"), into a NaiveDate.
+/// A task with fields derived from it as of a given day, for `list --json-extended`.
+/// Serializes as the stored task's fields followed by the computed ones.
+#[derive(Debug, Serialize)]
+pub struct TaskView<'a> {
+    #[serde(flatten)]
+    pub task: &'a Task,
+    pub is_overdue: bool,
+    pub days_until_due: Option<i64>,
+    pub days_until_thaw: Option<i64>,
+    pub age_days: i64,
+    pub is_blocked: bool,
+}
+
+impl<'a> TaskView<'a> {
+    /// `blocked` comes from the whole task list (see `deps::is_blocked`)
+    pub fn new(task: &'a Task, today: NaiveDate, blocked: bool) -> Self {
+        let days_until = |date: Option<NaiveDate>| date.map(|d| (d - today).num_days());
+        Self {
+            task,
+            is_overdue: task.state != TaskState::Evaporated
+                && task.due_date.is_some_and(|due| due < today),
+            days_until_due: days_until(task.due_date),
+            days_until_thaw: days_until(task.thaw_date),
+            age_days: (today - task.created_at).num_days(),
+            is_blocked: blocked,
+        }
+    }
+}
+
+/// Rewrites the ISO date strings of one serialized task as epoch day numbers
+fn plain_date_fields(value: &mut serde_json::Value) {
+    for field in DATE_FIELDS {
+        let Some(slot) = value.get_mut(*field) else {
+            continue;
+        };
+        if let Some(date) = slot.as_str().and_then(|s| s.parse::<NaiveDate>().ok()) {
+            *slot = epoch_days(date).into();
+        }
+    }
+}
+
+/// JSON Schema (draft 2020-12) of the tasks file: a top-level array of tasks.
+/// Keep this in sync with `Task`; the tests check that every serialized field is described.
+pub fn tasks_file_schema() -> serde_json::Value {
+    let date = serde_json::json!({ "type": "string", "format": "date" });
+    let nullable_date = serde_json::json!({ "type": ["string", "null"], "format": "date" });
+    let state = serde_json::json!({
+        "type": "string",
+        "enum": ["iced", "melting", "melted", "evaporated"]
+    });
+    serde_json::json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "Kelvin tasks file",
+        "type": "array",
+        "items": { "$ref": "#/$defs/Task" },
+        "$defs": {
+            "Task": {
+                "type": "object",
+                "required": ["id", "title", "description", "state", "created_at"],
+                "properties": {
+                    "id": { "type": "integer", "minimum": 1 },
+                    "title": { "type": "string" },
+                    "description": { "type": "string" },
+                    "state": state,
+                    "thaw_date": nullable_date,
+                    "due_date": nullable_date,
+                    "created_at": date,
+                    "depends_on": {
+                        "type": "array",
+                        "items": { "type": "integer", "minimum": 1 },
+                        "description": "IDs of tasks that must be done before this one"
+                    },
+                    "previous_state": {
+                        "anyOf": [state, { "type": "null" }],
+                        "description": "State before the task was burned, restored by cool"
+                    },
+                    "priority": {
+                        "type": "string",
+                        "enum": ["low", "normal", "high"],
+                        "default": "normal"
+                    },
+                    "tags": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Free-form labels, stored lowercase"
+                    },
+                    "completed_at": {
+                        "type": ["string", "null"],
+                        "format": "date",
+                        "description": "Day the task was burned"
+                    },
+                    "url": {
+                        "type": ["string", "null"],
+                        "format": "uri",
+                        "description": "Link to a related ticket, PR or document"
+                    },
+                    "estimate": {
+                        "type": ["integer", "null"],
+                        "minimum": 0,
+                        "description": "Estimated effort in minutes"
+                    },
+                    "subtasks": {
+                        "type": "array",
+                        "description": "Checklist of smaller steps",
+                        "items": {
+                            "type": "object",
+                            "required": ["title"],
+                            "properties": {
+                                "title": { "type": "string" },
+                                "done": { "type": "boolean", "default": false }
+                            }
+                        }
+                    },
+                    "updated_at": {
+                        "type": ["string", "null"],
+                        "format": "date",
+                        "description": "Day of the last change"
+                    },
+                    "burn_pending_until": {
+                        "type": ["string", "null"],
+                        "format": "date",
+                        "description": "Until this day the burn can be taken back with `unburn`"
+                    },
+                    "remind": {
+                        "type": ["string", "null"],
+                        "pattern": "^[0-9]+[dw]$",
+                        "description": "Lead time before the due date for `kelvin reminders`"
+                    },
+                    "thawed_at": {
+                        "type": ["string", "null"],
+                        "format": "date",
+                        "description": "Day the task last left Iced"
+                    },
+                    "frozen_at": {
+                        "type": ["string", "null"],
+                        "format": "date",
+                        "description": "Day the task last entered Iced"
+                    }
+                }
+            }
+        }
+    })
+}
+
+/// Largest accepted relative offset (about 100 years). Anything beyond is almost certainly a typo.
+const MAX_RELATIVE_DAYS: u64 = 36_525;
+
+/// Parses a date specification string, either relative ("3d", "1w", "today", "tomorrow", "fri")
+/// or absolute ("2026-03-01"), into a NaiveDate.
+/// A weekday name means the next such day, a week ahead when `base` already is one.
 pub fn parse_date_spec(spec: &str, base: NaiveDate) -> anyhow::Result<NaiveDate> {
-    // Relative date: Number + 'd' or 'w'
-    if let Some(num_str) = spec.strip_suffix('d') {
-        let days: i64 = num_str
-            .parse()
-            .map_err(|_| anyhow::anyhow!("Invalid relative date format: {spec}"))?;
+    if spec.eq_ignore_ascii_case("today") {
+        return Ok(base);
+    }
+    if spec.eq_ignore_ascii_case("tomorrow") {
+        return base.succ_opt().ok_or_else(|| anyhow::anyhow!("Date overflow"));
+    }
+    // chrono accepts "fri" and "Friday", ignoring case
+    if let Ok(weekday) = spec.parse::<Weekday>() {
+        let ahead = (i64::from(weekday.num_days_from_monday())
+            - i64::from(base.weekday().num_days_from_monday()))
+        .rem_euclid(7);
+        let ahead = if ahead == 0 { 7 } else { ahead };
         return base
-            .checked_add_days(chrono::Days::new(days as u64))
+            .checked_add_days(chrono::Days::new(ahead as u64))
             .ok_or_else(|| anyhow::anyhow!("Date overflow"));
     }
-    if let Some(num_str) = spec.strip_suffix('w') {
-        let weeks: i64 = num_str
+    // Relative date: Number + 'd' or 'w'
+    let relative = if let Some(num_str) = spec.strip_suffix('d') {
+        Some((num_str, 1))
+    } else {
+        spec.strip_suffix('w').map(|num_str| (num_str, 7))
+    };
+    if let Some((num_str, unit_days)) = relative {
+        let count: u64 = num_str
             .parse()
             .map_err(|_| anyhow::anyhow!("Invalid relative date format: {spec}"))?;
+        let days = count
+            .checked_mul(unit_days)
+            .filter(|days| *days <= MAX_RELATIVE_DAYS)
+            .ok_or_else(|| {
+                anyhow::anyhow!("Relative date '{spec}' is more than 100 years away; is it a typo?")
+            })?;
         return base
-            .checked_add_days(chrono::Days::new((weeks * 7) as u64))
+            .checked_add_days(chrono::Days::new(days))
             .ok_or_else(|| anyhow::anyhow!("Date overflow"));
     }
     // Absolute date: YYYY-MM-DD
@@ -60,6 +527,109 @@ pub fn parse_date_spec(spec: &str, base: NaiveDate) -> anyhow::Result<NaiveDate>
         .map_err(|e| anyhow::anyhow!("Invalid date format '{spec}': {e}"))
 }
 
+/// Like `parse_date_spec`, but relative dates count backwards: "3d" is three days before `base`,
+/// and a weekday name is the last such day (or `base` itself).
+/// Absolute dates, "today" and "tomorrow" mean the same day as in `parse_date_spec`.
+pub fn parse_past_date_spec(spec: &str, base: NaiveDate) -> anyhow::Result<NaiveDate> {
+    let date = parse_date_spec(spec, base)?;
+    if spec.parse::<Weekday>().is_ok() {
+        return date
+            .checked_sub_days(chrono::Days::new(7))
+            .ok_or_else(|| anyhow::anyhow!("Date overflow"));
+    }
+    let relative = spec
+        .strip_suffix(['d', 'w'])
+        .is_some_and(|count| count.parse::<u64>().is_ok());
+    if !relative {
+        return Ok(date);
+    }
+    base.checked_sub_signed(date - base)
+        .ok_or_else(|| anyhow::anyhow!("Date overflow"))
+}
+
+/// The first day on or after `earliest` that falls on the same weekday as `like`,
+/// e.g. a Tuesday due date deferred by 10 days lands on the Tuesday two weeks later
+pub fn same_weekday_on_or_after(like: NaiveDate, earliest: NaiveDate) -> anyhow::Result<NaiveDate> {
+    let ahead = (i64::from(like.weekday().num_days_from_monday())
+        - i64::from(earliest.weekday().num_days_from_monday()))
+    .rem_euclid(7);
+    earliest
+        .checked_add_days(chrono::Days::new(ahead as u64))
+        .ok_or_else(|| anyhow::anyhow!("Date overflow"))
+}
+
+/// Splits a trailing "by <date>" or "due <date>" phrase off a title: "Submit report by fri"
+/// gives ("Submit report", next Friday). The date must be a single word that `parse_date_spec`
+/// accepts and something must be left of the title; otherwise the result is None.
+pub fn extract_due_phrase(title: &str, today: NaiveDate) -> Option<(String, NaiveDate)> {
+    let (rest, spec) = title.trim_end().rsplit_once(char::is_whitespace)?;
+    let (rest, keyword) = rest.trim_end().rsplit_once(char::is_whitespace)?;
+    if !keyword.eq_ignore_ascii_case("by") && !keyword.eq_ignore_ascii_case("due") {
+        return None;
+    }
+    let rest = rest.trim();
+    if rest.is_empty() {
+        return None;
+    }
+    let date = parse_date_spec(spec, today).ok()?;
+    Some((rest.to_string(), date))
+}
+
+/// Parses a span of days such as "7d" or "2w" into a number of days
+pub fn parse_day_span(spec: &str) -> anyhow::Result<u32> {
+    let invalid =
+        || anyhow::anyhow!("Invalid span '{spec}' (expected days or weeks, e.g. 7d or 2w)");
+    let (count, unit_days) = if let Some(count) = spec.strip_suffix('d') {
+        (count, 1)
+    } else if let Some(count) = spec.strip_suffix('w') {
+        (count, 7)
+    } else {
+        return Err(invalid());
+    };
+    count
+        .parse::<u32>()
+        .ok()
+        .and_then(|count| count.checked_mul(unit_days))
+        .ok_or_else(invalid)
+}
+
+/// Parses an effort estimate such as "45m", "2h" or "1h30m" into minutes
+pub fn parse_duration(spec: &str) -> anyhow::Result<u32> {
+    let invalid = || anyhow::anyhow!("Invalid duration '{spec}' (expected e.g. 45m, 2h or 1h30m)");
+    let mut minutes: u32 = 0;
+    let mut number = String::new();
+    for c in spec.trim().chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        let factor = match c {
+            'h' => 60,
+            'm' => 1,
+            _ => return Err(invalid()),
+        };
+        let count: u32 = number.parse().map_err(|_| invalid())?;
+        minutes = count
+            .checked_mul(factor)
+            .and_then(|m| minutes.checked_add(m))
+            .ok_or_else(invalid)?;
+        number.clear();
+    }
+    if !number.is_empty() || spec.trim().is_empty() {
+        return Err(invalid());
+    }
+    Ok(minutes)
+}
+
+/// Formats minutes as "1h30m", "2h" or "45m"
+pub fn format_duration(minutes: u32) -> String {
+    match (minutes / 60, minutes % 60) {
+        (0, m) => format!("{m}m"),
+        (h, 0) => format!("{h}h"),
+        (h, m) => format!("{h}h{m}m"),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -78,6 +648,143 @@ mod tests {
         assert_eq!(result, NaiveDate::from_ymd_opt(2026, 1, 15).unwrap());
     }
 
+    #[test]
+    fn parse_past_dates_count_backwards() {
+        let base = NaiveDate::from_ymd_opt(2026, 1, 10).unwrap();
+        assert_eq!(
+            parse_past_date_spec("3d", base).unwrap(),
+            NaiveDate::from_ymd_opt(2026, 1, 7).unwrap()
+        );
+        assert_eq!(
+            parse_past_date_spec("1w", base).unwrap(),
+            NaiveDate::from_ymd_opt(2026, 1, 3).unwrap()
+        );
+        assert_eq!(parse_past_date_spec("today", base).unwrap(), base);
+        assert_eq!(
+            parse_past_date_spec("2026-01-01", base).unwrap(),
+            NaiveDate::from_ymd_opt(2026, 1, 1).unwrap()
+        );
+        // Only relative specs are mirrored; later named and absolute dates stay as given
+        assert_eq!(
+            parse_past_date_spec("2026-03-01", base).unwrap(),
+            NaiveDate::from_ymd_opt(2026, 3, 1).unwrap()
+        );
+        assert_eq!(
+            parse_past_date_spec("tomorrow", base).unwrap(),
+            NaiveDate::from_ymd_opt(2026, 1, 11).unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_day_keywords() {
+        // 2026-01-01 is a Thursday
+        let base = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+        let day = |d| NaiveDate::from_ymd_opt(2026, 1, d).unwrap();
+        assert_eq!(parse_date_spec("Tomorrow", base).unwrap(), day(2));
+        assert_eq!(parse_date_spec("fri", base).unwrap(), day(2));
+        assert_eq!(parse_date_spec("Monday", base).unwrap(), day(5));
+        assert_eq!(parse_date_spec("thu", base).unwrap(), day(8));
+        assert_eq!(parse_past_date_spec("thu", day(8)).unwrap(), day(8));
+        assert_eq!(parse_past_date_spec("mon", day(8)).unwrap(), day(5));
+    }
+
+    #[test]
+    fn due_phrase_is_extracted_only_when_recognized() {
+        let today = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+        assert_eq!(
+            extract_due_phrase("Submit report by Friday", today),
+            Some(("Submit report".to_string(), NaiveDate::from_ymd_opt(2026, 1, 2).unwrap()))
+        );
+        assert_eq!(
+            extract_due_phrase("Renew passport due 2026-03-01 ", today),
+            Some(("Renew passport".to_string(), NaiveDate::from_ymd_opt(2026, 3, 1).unwrap()))
+        );
+        assert_eq!(extract_due_phrase("Stand by me", today), None);
+        assert_eq!(extract_due_phrase("by friday", today), None);
+        assert_eq!(extract_due_phrase("Finish by next friday", today), None);
+        assert_eq!(extract_due_phrase("Report due", today), None);
+    }
+
+    #[test]
+    fn same_weekday_rolls_forward_across_weeks() {
+        let day = |m, d| NaiveDate::from_ymd_opt(2026, m, d).unwrap();
+        // 2026-01-06 is a Tuesday
+        let tuesday = day(1, 6);
+        assert_eq!(same_weekday_on_or_after(tuesday, day(1, 13)).unwrap(), day(1, 13));
+        assert_eq!(same_weekday_on_or_after(tuesday, day(1, 9)).unwrap(), day(1, 13));
+        assert_eq!(same_weekday_on_or_after(tuesday, day(1, 14)).unwrap(), day(1, 20));
+        // Across a month and a year boundary
+        assert_eq!(same_weekday_on_or_after(tuesday, day(1, 28)).unwrap(), day(2, 3));
+        let wednesday = NaiveDate::from_ymd_opt(2025, 12, 24).unwrap();
+        assert_eq!(same_weekday_on_or_after(wednesday, day(1, 1)).unwrap(), day(1, 7));
+        // No room left in the calendar
+        assert!(same_weekday_on_or_after(tuesday, NaiveDate::MAX).is_err());
+    }
+
+    #[test]
+    fn control_characters_are_cleaned_from_text() {
+        assert_eq!(clean_title("Fix\tlogin\r\nbug\u{7}"), "Fix login  bug ");
+        assert_eq!(clean_title("Café 日本"), "Café 日本");
+        assert_eq!(
+            clean_description("Steps:\r\n1.\tLog in\n2. \u{1b}[31mFail"),
+            "Steps:\n1. Log in\n2.  [31mFail"
+        );
+    }
+
+    #[test]
+    fn summary_counts_and_format() {
+        let today = NaiveDate::from_ymd_opt(2026, 1, 10).unwrap();
+        let task = |state, due: Option<u32>| Task {
+            title: "Task".to_string(),
+            state,
+            due_date: due.map(|d| NaiveDate::from_ymd_opt(2026, 1, d).unwrap()),
+            ..Task::default()
+        };
+        let tasks = vec![
+            task(TaskState::Melted, Some(10)),
+            task(TaskState::Melted, Some(9)),
+            task(TaskState::Iced, Some(10)),
+            task(TaskState::Evaporated, Some(1)),
+        ];
+        let counts = SummaryCounts::of(&tasks, today);
+        assert_eq!(counts.due_today, 2);
+        assert_eq!(counts.overdue, 1);
+        assert_eq!(counts.melted, 2);
+        assert_eq!(
+            counts.render(DEFAULT_SUMMARY_FORMAT).unwrap(),
+            "🔥2 due today · 2 melted · 1 overdue"
+        );
+        assert_eq!(counts.render("{iced}/{melting}").unwrap(), "1/0");
+        assert!(counts.render("{due}").unwrap_err().to_string().contains("'{due}'"));
+    }
+
+    #[test]
+    fn reminders_start_the_lead_time_before_due() {
+        let today = NaiveDate::from_ymd_opt(2026, 1, 10).unwrap();
+        let task = |state, due: u32, remind: &str| Task {
+            title: "Task".to_string(),
+            state,
+            due_date: NaiveDate::from_ymd_opt(2026, 1, due),
+            remind: Some(remind.to_string()),
+            ..Task::default()
+        };
+        assert!(task(TaskState::Melted, 12, "2d").reminder_active(today));
+        assert!(!task(TaskState::Melted, 13, "2d").reminder_active(today));
+        assert!(task(TaskState::Iced, 5, "0d").reminder_active(today));
+        assert!(task(TaskState::Melted, 24, "2w").reminder_active(today));
+        assert!(!task(TaskState::Evaporated, 12, "2d").reminder_active(today));
+        assert!(!task(TaskState::Melted, 12, "soon").reminder_active(today));
+
+        let tasks = [task(TaskState::Melted, 12, "2d"), task(TaskState::Melted, 20, "2d")];
+        assert_eq!(SummaryCounts::of(&tasks, today).render("⏰{reminders}").unwrap(), "⏰1");
+    }
+
+    #[test]
+    fn parse_today_keyword() {
+        let base = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+        assert_eq!(parse_date_spec("today", base).unwrap(), base);
+    }
+
     #[test]
     fn parse_absolute_date() {
         let base = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
@@ -92,6 +799,62 @@ mod tests {
         assert!(parse_date_spec("3x", base).is_err());
     }
 
+    #[test]
+    fn parse_rejects_implausibly_large_offsets() {
+        let base = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+        let err = parse_date_spec("9999999999d", base).unwrap_err();
+        assert!(err.to_string().contains("more than 100 years"));
+        assert!(parse_date_spec("5300w", base).is_err());
+        assert!(parse_date_spec("36525d", base).is_ok());
+    }
+
+    #[test]
+    fn parse_rejects_negative_offsets() {
+        let base = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+        assert!(parse_date_spec("-3d", base).is_err());
+    }
+
+    #[test]
+    fn humanize_relative_dates() {
+        let today = NaiveDate::from_ymd_opt(2026, 2, 24).unwrap();
+        let day = |d| NaiveDate::from_ymd_opt(2026, 2, d).unwrap();
+        assert_eq!(humanize_date(today, today, 0).unwrap(), "today");
+        assert_eq!(humanize_date(day(25), today, 0).unwrap(), "in 1 day");
+        assert_eq!(humanize_date(day(28), today, 0).unwrap(), "in 4 days");
+        assert_eq!(humanize_date(day(22), today, 0).unwrap(), "2 days ago");
+    }
+
+    #[test]
+    fn humanize_weekdays_within_window() {
+        // A Tuesday
+        let today = NaiveDate::from_ymd_opt(2026, 3, 3).unwrap();
+        let day = |d| NaiveDate::from_ymd_opt(2026, 3, d).unwrap();
+        assert_eq!(humanize_date(today, today, 7).unwrap(), "today");
+        assert_eq!(humanize_date(day(4), today, 7).unwrap(), "Wed");
+        assert_eq!(humanize_date(day(8), today, 7).unwrap(), "Sun");
+        assert_eq!(humanize_date(day(9), today, 7).unwrap(), "next Mon");
+        assert_eq!(humanize_date(day(10), today, 7).unwrap(), "next Tue");
+        assert_eq!(humanize_date(day(11), today, 7).unwrap(), "in 8 days");
+        assert_eq!(humanize_date(day(6), today, 2).unwrap(), "in 3 days");
+        assert_eq!(humanize_date(day(17), today, 7).unwrap(), "in 14 days");
+        assert_eq!(humanize_date(day(18), today, 7), None);
+        assert_eq!(humanize_date(day(1), today, 7).unwrap(), "2 days ago");
+    }
+
+    #[test]
+    fn durations_round_trip() {
+        assert_eq!(parse_duration("45m").unwrap(), 45);
+        assert_eq!(parse_duration("2h").unwrap(), 120);
+        assert_eq!(parse_duration("1h30m").unwrap(), 90);
+        assert_eq!(parse_duration("0m").unwrap(), 0);
+        for bad in ["", "90", "h", "1.5h", "2d", "1h30"] {
+            assert!(parse_duration(bad).is_err(), "{bad} should be rejected");
+        }
+        assert_eq!(format_duration(45), "45m");
+        assert_eq!(format_duration(120), "2h");
+        assert_eq!(format_duration(90), "1h30m");
+    }
+
     #[test]
     fn task_state_display() {
         assert_eq!(format!("{}", TaskState::Iced), "Iced");
@@ -101,16 +864,85 @@ mod tests {
     }
 
     #[test]
-    fn task_serialization_roundtrip() {
+    fn schema_describes_every_serialized_field() {
         let task = Task {
-            id: 1,
-            title: "Test".to_string(),
-            description: String::new(),
-            state: TaskState::Melted,
-            thaw_date: None,
-            due_date: None,
-            created_at: NaiveDate::from_ymd_opt(2026, 1, 1).unwrap(),
+            state: TaskState::Evaporated,
+            thaw_date: Some(NaiveDate::from_ymd_opt(2026, 1, 2).unwrap()),
+            due_date: Some(NaiveDate::from_ymd_opt(2026, 1, 3).unwrap()),
+            depends_on: vec![2],
+            previous_state: Some(TaskState::Melted),
+            priority: Priority::High,
+            tags: vec!["work".to_string()],
+            completed_at: Some(NaiveDate::from_ymd_opt(2026, 1, 4).unwrap()),
+            url: Some("https://example.com/1".to_string()),
+            estimate: Some(90),
+            subtasks: vec![Subtask {
+                title: "Step".to_string(),
+                done: true,
+            }],
+            updated_at: Some(NaiveDate::from_ymd_opt(2026, 1, 4).unwrap()),
+            ..Task::default()
         };
+        let schema = tasks_file_schema();
+        let properties = schema["$defs"]["Task"]["properties"].as_object().unwrap();
+        let value = serde_json::to_value(&task).unwrap();
+        for key in value.as_object().unwrap().keys() {
+            assert!(properties.contains_key(key), "schema is missing '{key}'");
+        }
+    }
+
+    #[test]
+    fn duplicate_titles_are_matched_after_normalization() {
+        let make = |id, title: &str, state| Task {
+            id,
+            title: title.to_string(),
+            state,
+            ..Task::default()
+        };
+        let tasks = vec![
+            make(1, "Old report", TaskState::Evaporated),
+            make(2, "Write Report ", TaskState::Iced),
+        ];
+        assert_eq!(find_duplicate_title(&tasks, "  write report").map(|t| t.id), Some(2));
+        assert!(find_duplicate_title(&tasks, "old report").is_none());
+        assert!(find_duplicate_title(&tasks, "write reports").is_none());
+    }
+
+    #[test]
+    fn date_fields_match_schema() {
+        let schema = tasks_file_schema();
+        let properties = schema["$defs"]["Task"]["properties"].as_object().unwrap();
+        let mut dated: Vec<&str> = properties
+            .iter()
+            .filter(|(_, p)| p["format"] == "date")
+            .map(|(key, _)| key.as_str())
+            .collect();
+        let mut expected = DATE_FIELDS.to_vec();
+        dated.sort();
+        expected.sort();
+        assert_eq!(dated, expected);
+    }
+
+    #[test]
+    fn plain_dates_are_days_since_unix_epoch() {
+        assert_eq!(epoch_days(NaiveDate::from_ymd_opt(1970, 1, 1).unwrap()), 0);
+        let task = Task {
+            state: TaskState::Iced,
+            thaw_date: Some(NaiveDate::from_ymd_opt(1970, 1, 11).unwrap()),
+            created_at: NaiveDate::from_ymd_opt(1970, 1, 2).unwrap(),
+            ..Task::default()
+        };
+        let json = tasks_to_json([&task], true).unwrap();
+        assert_eq!(json[0]["thaw_date"], 10);
+        assert_eq!(json[0]["created_at"], 1);
+        assert!(json[0]["due_date"].is_null());
+        let json = tasks_to_json([&task], false).unwrap();
+        assert_eq!(json[0]["created_at"], "1970-01-02");
+    }
+
+    #[test]
+    fn task_serialization_roundtrip() {
+        let task = Task::default();
         let json = serde_json::to_string(&task).unwrap();
         let deserialized: Task = serde_json::from_str(&json).unwrap();
         assert_eq!(deserialized.id, task.id);
@@ -0,0 +1,100 @@
+use std::io::{self, Write};
+
+use anyhow::{bail, Result};
+
+use crate::cli::NotifyMode;
+use crate::models::Task;
+
+/// Fails early when `mode` cannot work in this build
+pub fn check_available(mode: NotifyMode) -> Result<()> {
+    if mode == NotifyMode::Desktop && !cfg!(feature = "desktop-notify") {
+        bail!(
+            "Desktop notifications are not available in this build (enable the `desktop-notify` feature)"
+        );
+    }
+    Ok(())
+}
+
+/// Alerts the user that the tasks in `thawed` have just thawed (no-op when it is empty)
+pub fn thawed(tasks: &[Task], thawed: &[u32], mode: NotifyMode) -> Result<()> {
+    if thawed.is_empty() {
+        return Ok(());
+    }
+    match mode {
+        NotifyMode::Bell => {
+            let mut stdout = io::stdout();
+            stdout.write_all(b"\x07")?;
+            stdout.flush()?;
+        }
+        NotifyMode::Desktop => {
+            let noun = if thawed.len() == 1 { "task" } else { "tasks" };
+            desktop(&format!("Kelvin: {} {noun} thawed", thawed.len()), &message(tasks, thawed))?;
+        }
+    }
+    Ok(())
+}
+
+/// One line per thawed task, e.g. "#3 Write report"
+fn message(tasks: &[Task], thawed: &[u32]) -> String {
+    tasks
+        .iter()
+        .filter(|t| thawed.contains(&t.id))
+        .map(|t| format!("#{} {}", t.id, t.title))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Shows a desktop notification through the platform's notifier
+/// (`osascript` on macOS, `notify-send` elsewhere)
+#[cfg(feature = "desktop-notify")]
+fn desktop(summary: &str, body: &str) -> Result<()> {
+    use anyhow::Context;
+    use std::process::Command;
+
+    let (program, status) = if cfg!(target_os = "macos") {
+        let script = format!("display notification {body:?} with title {summary:?}");
+        ("osascript", Command::new("osascript").arg("-e").arg(script).status())
+    } else {
+        ("notify-send", Command::new("notify-send").arg(summary).arg(body).status())
+    };
+    let status = status.with_context(|| format!("Failed to run {program}"))?;
+    if !status.success() {
+        bail!("{program} exited with {status}");
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "desktop-notify"))]
+fn desktop(_summary: &str, _body: &str) -> Result<()> {
+    unreachable!("rejected by check_available")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::TaskState;
+
+    fn task(id: u32, title: &str) -> Task {
+        Task {
+            id,
+            title: title.to_string(),
+            state: TaskState::Melting,
+            ..Task::default()
+        }
+    }
+
+    #[test]
+    fn message_lists_only_thawed_tasks() {
+        let tasks = vec![task(1, "Write report"), task(2, "Call Bob"), task(3, "Pay rent")];
+        assert_eq!(message(&tasks, &[1, 3]), "#1 Write report\n#3 Pay rent");
+    }
+
+    #[test]
+    fn desktop_mode_depends_on_the_build() {
+        assert!(check_available(NotifyMode::Bell).is_ok());
+        assert_eq!(
+            check_available(NotifyMode::Desktop).is_ok(),
+            cfg!(feature = "desktop-notify")
+        );
+    }
+}
@@ -1,74 +1,241 @@
+use std::fmt;
+
 use anyhow::{bail, Result};
 use chrono::NaiveDate;
 
 use crate::models::{Task, TaskState};
 
-/// Automatically transition Iced tasks that have passed their thaw date to the Melting state during command execution.
-/// Returns the number of tasks that were updated.
-pub fn auto_warm(tasks: &mut [Task], today: NaiveDate) -> u32 {
-    let mut count = 0;
+/// Something that moves a task to another state: a command, or the automatic thaw
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Thaw,
+    Warm,
+    Burn,
+    Cool,
+    Freeze,
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Action::Thaw => "thaw",
+            Action::Warm => "warm",
+            Action::Burn => "burn",
+            Action::Cool => "cool",
+            Action::Freeze => "freeze",
+        };
+        write!(f, "{s}")
+    }
+}
+
+/// A legal phase transition
+pub struct Transition {
+    pub action: Action,
+    /// Past tense, for messages ("warmed")
+    pub done: &'static str,
+    pub from: &'static [TaskState],
+    /// Possible target states; more than one when the outcome depends on the task's history
+    pub to: &'static [TaskState],
+    pub note: &'static str,
+}
+
+/// The state machine. The transition functions below check against this table and
+/// `kelvin states` prints it, so the documented rules are the enforced ones.
+pub const TRANSITIONS: &[Transition] = &[
+    Transition {
+        action: Action::Thaw,
+        done: "thawed",
+        from: &[TaskState::Iced],
+        to: &[TaskState::Melting, TaskState::Melted],
+        note: "automatic, once the thaw date is reached (Melted with defaults.thaw_to)",
+    },
+    Transition {
+        action: Action::Warm,
+        done: "warmed",
+        from: &[TaskState::Iced, TaskState::Melting],
+        to: &[TaskState::Melted],
+        note: "ready to work on",
+    },
+    Transition {
+        action: Action::Burn,
+        done: "burned",
+        from: &[TaskState::Melted, TaskState::Iced],
+        to: &[TaskState::Evaporated],
+        note: "done",
+    },
+    Transition {
+        action: Action::Cool,
+        done: "cooled",
+        from: &[TaskState::Evaporated],
+        to: &[TaskState::Melted, TaskState::Iced],
+        note: "back to the state before burn (Iced only if it still has a thaw date)",
+    },
+    Transition {
+        action: Action::Freeze,
+        done: "frozen",
+        from: &[
+            TaskState::Iced,
+            TaskState::Melting,
+            TaskState::Melted,
+            TaskState::Evaporated,
+        ],
+        to: &[TaskState::Iced],
+        note: "postponed until a thaw date",
+    },
+];
+
+/// The table entry for `action`
+pub fn transition(action: Action) -> &'static Transition {
+    TRANSITIONS
+        .iter()
+        .find(|t| t.action == action)
+        .expect("every action is listed in TRANSITIONS")
+}
+
+/// Fails unless `action` may be applied to `task` in its current state
+fn check(action: Action, task: &Task) -> Result<()> {
+    let transition = transition(action);
+    if transition.from.contains(&task.state) {
+        return Ok(());
+    }
+    let from: Vec<String> = transition.from.iter().map(ToString::to_string).collect();
+    bail!(
+        "Cannot {action} task {} (state: {}). Only {} tasks can be {}.",
+        task.id,
+        task.state,
+        from.join(" or "),
+        transition.done
+    )
+}
+
+/// Automatically transition Iced tasks that have passed their thaw date to the `to` state
+/// (Melting or Melted) during command execution. Returns the IDs of the tasks that thawed.
+pub fn auto_warm(tasks: &mut [Task], today: NaiveDate, to: TaskState) -> Vec<u32> {
+    let mut thawed = Vec::new();
     for task in tasks.iter_mut() {
-        if task.state == TaskState::Iced {
-            if let Some(thaw_date) = task.thaw_date {
-                if today >= thaw_date {
-                    task.state = TaskState::Melting;
-                    count += 1;
-                }
-            }
+        if task.state == TaskState::Iced
+            && let Some(thaw_date) = task.thaw_date
+            && today >= thaw_date
+            && thaw(task, to, thaw_date).is_ok()
+        {
+            thawed.push(task.id);
         }
     }
-    count
+    thawed
 }
 
-/// Melting/Iced -> Melted: Set the task to a ready (Melted) state.
-pub fn warm(task: &mut Task) -> Result<()> {
-    match task.state {
-        TaskState::Melting | TaskState::Iced => {
-            task.state = TaskState::Melted;
-            task.thaw_date = None;
-            Ok(())
+/// Iced -> `to` (Melting or Melted): Thaw the task as of `on`, which becomes its thaw date
+/// (earlier than planned when a blocker is burned with `--cascade`).
+pub fn thaw(task: &mut Task, to: TaskState, on: NaiveDate) -> Result<()> {
+    check(Action::Thaw, task)?;
+    debug_assert!(transition(Action::Thaw).to.contains(&to));
+    task.state = to;
+    task.thaw_date = Some(on);
+    task.thawed_at = Some(on);
+    Ok(())
+}
+
+/// Makes burns whose grace period has ended final: `unburn` no longer applies to them.
+/// Returns the number of burns committed.
+pub fn commit_burns(tasks: &mut [Task], today: NaiveDate) -> usize {
+    let mut committed = 0;
+    for task in tasks.iter_mut() {
+        if task.burn_pending_until.is_some_and(|until| today >= until) {
+            task.burn_pending_until = None;
+            committed += 1;
         }
-        _ => bail!(
-            "Cannot warm task {} (state: {}). Only Iced or Melting tasks can be warmed.",
-            task.id,
-            task.state
-        ),
     }
+    committed
 }
 
-/// Melted/Iced -> Evaporated: Complete (evaporate) the task.
-pub fn burn(task: &mut Task) -> Result<()> {
-    match task.state {
-        TaskState::Melted | TaskState::Iced => {
-            task.state = TaskState::Evaporated;
-            Ok(())
+/// Permanently removes tasks that were burned at least `days` days before `today`.
+/// Evaporated tasks without a `completed_at` date, or whose burn is still pending, are kept.
+/// Dependencies on the removed tasks are dropped from the remaining ones.
+/// Returns the number removed.
+pub fn purge_evaporated(tasks: &mut Vec<Task>, today: NaiveDate, days: u32) -> usize {
+    let before = tasks.len();
+    let mut purged = Vec::new();
+    tasks.retain(|task| {
+        let expired = task.state == TaskState::Evaporated
+            && task.burn_pending_until.is_none()
+            && task
+                .completed_at
+                .is_some_and(|done| (today - done).num_days() >= i64::from(days));
+        if expired {
+            purged.push(task.id);
         }
-        _ => bail!(
-            "Cannot burn task {} (state: {}). Only Melted or Iced tasks can be burned.",
-            task.id,
-            task.state
-        ),
+        !expired
+    });
+    for task in tasks.iter_mut() {
+        task.depends_on.retain(|dep| !purged.contains(dep));
     }
+    before - tasks.len()
 }
 
-/// Evaporated -> Melted: Cancel completion and return the task to a Melted state.
+/// Melting/Iced -> Melted: Set the task to a ready (Melted) state on `today`.
+pub fn warm(task: &mut Task, today: NaiveDate) -> Result<()> {
+    check(Action::Warm, task)?;
+    if task.state == TaskState::Iced {
+        task.thawed_at = Some(today);
+    }
+    task.state = TaskState::Melted;
+    task.thaw_date = None;
+    Ok(())
+}
+
+/// Melted/Iced -> Evaporated: Complete (evaporate) the task on `today`.
+/// The prior state is remembered so `cool` can undo the burn.
+pub fn burn(task: &mut Task, today: NaiveDate) -> Result<()> {
+    check(Action::Burn, task)?;
+    if task.state == TaskState::Iced {
+        task.thawed_at = Some(today);
+    }
+    task.previous_state = Some(task.state);
+    task.state = TaskState::Evaporated;
+    task.completed_at = Some(today);
+    Ok(())
+}
+
+/// `burn`, leaving the burn open to `unburn` for `grace_days` days (0 makes it final at once)
+pub fn burn_with_grace(task: &mut Task, today: NaiveDate, grace_days: u32) -> Result<()> {
+    burn(task, today)?;
+    task.burn_pending_until = (grace_days > 0)
+        .then(|| today.checked_add_days(chrono::Days::new(u64::from(grace_days))))
+        .flatten();
+    Ok(())
+}
+
+/// Evaporated -> (pre-burn state): Cancel completion.
+/// A task that was Iced when burned is refrozen with its old thaw date; otherwise it returns to Melted.
 pub fn cool(task: &mut Task) -> Result<()> {
-    match task.state {
-        TaskState::Evaporated => {
+    check(Action::Cool, task)?;
+    task.completed_at = None;
+    task.burn_pending_until = None;
+    match task.previous_state.take() {
+        Some(TaskState::Iced) if task.thaw_date.is_some() => {
+            task.state = TaskState::Iced;
+        }
+        _ => {
             task.state = TaskState::Melted;
             task.thaw_date = None;
-            Ok(())
         }
-        _ => bail!(
-            "Cannot cool task {} (state: {}). Only Evaporated tasks can be cooled.",
-            task.id,
-            task.state
-        ),
     }
+    Ok(())
 }
 
-/// Any State -> Iced: Refreeze the task. A thaw date is required.
-pub fn freeze(task: &mut Task, thaw_date: NaiveDate) -> Result<()> {
+/// Any State -> Iced: Refreeze the task on `today`. A thaw date is required.
+/// Moving the thaw date of an Iced task keeps the day it froze; a burned task is reopened,
+/// dropping its completion like `cool` does.
+pub fn freeze(task: &mut Task, thaw_date: NaiveDate, today: NaiveDate) -> Result<()> {
+    check(Action::Freeze, task)?;
+    if task.state == TaskState::Evaporated {
+        task.completed_at = None;
+        task.burn_pending_until = None;
+        task.previous_state = None;
+    }
+    if task.state != TaskState::Iced {
+        task.frozen_at = Some(today);
+    }
     task.state = TaskState::Iced;
     task.thaw_date = Some(thaw_date);
     Ok(())
@@ -81,13 +248,52 @@ mod tests {
 
     fn make_task(state: TaskState, thaw_date: Option<NaiveDate>) -> Task {
         Task {
-            id: 1,
-            title: "Test".to_string(),
-            description: String::new(),
             state,
             thaw_date,
-            due_date: None,
-            created_at: NaiveDate::from_ymd_opt(2026, 1, 1).unwrap(),
+            ..Task::default()
+        }
+    }
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 1, 10).unwrap()
+    }
+
+    // --- transition table ---
+    /// Applies `action` to `task`, returning whether it was allowed
+    fn apply(action: Action, task: &mut Task) -> bool {
+        match action {
+            Action::Thaw => {
+                !auto_warm(std::slice::from_mut(task), today(), TaskState::Melting).is_empty()
+            }
+            Action::Warm => warm(task, today()).is_ok(),
+            Action::Burn => burn(task, today()).is_ok(),
+            Action::Cool => cool(task).is_ok(),
+            Action::Freeze => freeze(task, today(), today()).is_ok(),
+        }
+    }
+
+    #[test]
+    fn transitions_match_the_table() {
+        let states = [
+            TaskState::Iced,
+            TaskState::Melting,
+            TaskState::Melted,
+            TaskState::Evaporated,
+        ];
+        for state in states {
+            // Cool's outcome depends on the state before the burn
+            for previous_state in [None, Some(TaskState::Iced)] {
+                for transition in TRANSITIONS {
+                    let mut task = make_task(state, Some(today()));
+                    task.previous_state = previous_state;
+                    let applied = apply(transition.action, &mut task);
+                    let action = transition.action;
+                    assert_eq!(applied, transition.from.contains(&state), "{action} from {state}");
+                    if applied {
+                        assert!(transition.to.contains(&task.state), "{action} from {state}");
+                    }
+                }
+            }
         }
     }
 
@@ -99,9 +305,38 @@ mod tests {
             Some(NaiveDate::from_ymd_opt(2026, 1, 5).unwrap()),
         )];
         let today = NaiveDate::from_ymd_opt(2026, 1, 5).unwrap();
-        let count = auto_warm(&mut tasks, today);
-        assert_eq!(count, 1);
+        assert_eq!(auto_warm(&mut tasks, today, TaskState::Melting), vec![tasks[0].id]);
         assert_eq!(tasks[0].state, TaskState::Melting);
+        assert_eq!(tasks[0].thawed_at, Some(today));
+    }
+
+    #[test]
+    fn auto_warm_can_thaw_straight_to_melted() {
+        let mut tasks = vec![make_task(TaskState::Iced, Some(today()))];
+        assert_eq!(auto_warm(&mut tasks, today(), TaskState::Melted), vec![tasks[0].id]);
+        assert_eq!(tasks[0].state, TaskState::Melted);
+    }
+
+    #[test]
+    fn thaw_ahead_of_schedule_moves_the_thaw_date() {
+        let mut task = make_task(TaskState::Iced, NaiveDate::from_ymd_opt(2026, 2, 1));
+        thaw(&mut task, TaskState::Melted, today()).unwrap();
+        assert_eq!(task.state, TaskState::Melted);
+        assert_eq!(task.thaw_date, Some(today()));
+        assert_eq!(task.thawed_at, Some(today()));
+        assert!(thaw(&mut task, TaskState::Melting, today()).is_err());
+    }
+
+    #[test]
+    fn commit_burns_ends_expired_grace_periods() {
+        let mut pending = make_task(TaskState::Evaporated, None);
+        pending.burn_pending_until = today().succ_opt();
+        let mut expired = make_task(TaskState::Evaporated, None);
+        expired.burn_pending_until = Some(today());
+        let mut tasks = vec![pending, expired];
+        assert_eq!(commit_burns(&mut tasks, today()), 1);
+        assert!(tasks[0].burn_pending_until.is_some());
+        assert!(tasks[1].burn_pending_until.is_none());
     }
 
     #[test]
@@ -111,8 +346,7 @@ mod tests {
             Some(NaiveDate::from_ymd_opt(2026, 1, 10).unwrap()),
         )];
         let today = NaiveDate::from_ymd_opt(2026, 1, 5).unwrap();
-        let count = auto_warm(&mut tasks, today);
-        assert_eq!(count, 0);
+        assert!(auto_warm(&mut tasks, today, TaskState::Melting).is_empty());
         assert_eq!(tasks[0].state, TaskState::Iced);
     }
 
@@ -120,8 +354,7 @@ mod tests {
     fn auto_warm_ignores_non_iced() {
         let mut tasks = vec![make_task(TaskState::Melted, None)];
         let today = NaiveDate::from_ymd_opt(2026, 1, 5).unwrap();
-        let count = auto_warm(&mut tasks, today);
-        assert_eq!(count, 0);
+        assert!(auto_warm(&mut tasks, today, TaskState::Melting).is_empty());
         assert_eq!(tasks[0].state, TaskState::Melted);
     }
 
@@ -129,7 +362,7 @@ mod tests {
     #[test]
     fn warm_melting_to_melted() {
         let mut task = make_task(TaskState::Melting, None);
-        warm(&mut task).unwrap();
+        warm(&mut task, today()).unwrap();
         assert_eq!(task.state, TaskState::Melted);
     }
 
@@ -139,28 +372,66 @@ mod tests {
             TaskState::Iced,
             Some(NaiveDate::from_ymd_opt(2026, 1, 1).unwrap()),
         );
-        warm(&mut task).unwrap();
+        warm(&mut task, today()).unwrap();
         assert_eq!(task.state, TaskState::Melted);
         assert_eq!(task.thaw_date, None);
+        assert_eq!(task.thawed_at, Some(today()));
+    }
+
+    #[test]
+    fn freeze_cycle_splits_frozen_and_active_days() {
+        let day = |d| NaiveDate::from_ymd_opt(2026, 1, d).unwrap();
+        let mut task = make_task(TaskState::Iced, Some(day(9)));
+        task.frozen_at = Some(day(1));
+        assert_eq!(task.frozen_and_active_days(), None);
+        warm(&mut task, day(5)).unwrap();
+        burn(&mut task, day(12)).unwrap();
+        assert_eq!(task.frozen_and_active_days(), Some((4, 7)));
+
+        // Burned straight from Iced: never active
+        let mut task = make_task(TaskState::Iced, Some(day(9)));
+        task.frozen_at = Some(day(1));
+        burn(&mut task, day(3)).unwrap();
+        assert_eq!(task.frozen_and_active_days(), Some((2, 0)));
+        // Never frozen
+        let mut task = make_task(TaskState::Melted, None);
+        burn(&mut task, day(3)).unwrap();
+        assert_eq!(task.frozen_and_active_days(), None);
+    }
+
+    #[test]
+    fn frozen_days_count_from_the_freeze_not_creation() {
+        let day = |d| NaiveDate::from_ymd_opt(2026, 1, d).unwrap();
+        // Created Melted on the 1st, worked on until the 21st, then frozen until the 25th
+        let mut task = make_task(TaskState::Melted, None);
+        freeze(&mut task, day(25), day(21)).unwrap();
+        assert_eq!(task.frozen_at, Some(day(21)));
+        // Moving the thaw date keeps the day it froze
+        freeze(&mut task, day(25), day(22)).unwrap();
+        assert_eq!(task.frozen_at, Some(day(21)));
+        assert_eq!(auto_warm(std::slice::from_mut(&mut task), day(25), TaskState::Melting), [1]);
+        warm(&mut task, day(26)).unwrap();
+        burn(&mut task, day(28)).unwrap();
+        assert_eq!(task.frozen_and_active_days(), Some((4, 3)));
     }
 
     #[test]
     fn warm_melted_fails() {
         let mut task = make_task(TaskState::Melted, None);
-        assert!(warm(&mut task).is_err());
+        assert!(warm(&mut task, today()).is_err());
     }
 
     #[test]
     fn warm_evaporated_fails() {
         let mut task = make_task(TaskState::Evaporated, None);
-        assert!(warm(&mut task).is_err());
+        assert!(warm(&mut task, today()).is_err());
     }
 
     // --- burn ---
     #[test]
     fn burn_melted_to_evaporated() {
         let mut task = make_task(TaskState::Melted, None);
-        burn(&mut task).unwrap();
+        burn(&mut task, today()).unwrap();
         assert_eq!(task.state, TaskState::Evaporated);
     }
 
@@ -170,14 +441,46 @@ mod tests {
             TaskState::Iced,
             Some(NaiveDate::from_ymd_opt(2026, 1, 1).unwrap()),
         );
-        burn(&mut task).unwrap();
+        burn(&mut task, today()).unwrap();
         assert_eq!(task.state, TaskState::Evaporated);
     }
 
+    #[test]
+    fn burn_with_grace_keeps_the_burn_pending() {
+        let mut task = make_task(TaskState::Melted, None);
+        burn_with_grace(&mut task, today(), 2).unwrap();
+        assert_eq!(task.burn_pending_until, NaiveDate::from_ymd_opt(2026, 1, 12));
+        let mut task = make_task(TaskState::Melted, None);
+        burn_with_grace(&mut task, today(), 0).unwrap();
+        assert_eq!(task.burn_pending_until, None);
+    }
+
     #[test]
     fn burn_evaporated_fails() {
         let mut task = make_task(TaskState::Evaporated, None);
-        assert!(burn(&mut task).is_err());
+        assert!(burn(&mut task, today()).is_err());
+    }
+
+    // --- purge_evaporated ---
+    #[test]
+    fn purge_removes_only_long_evaporated_tasks() {
+        let mut old = make_task(TaskState::Melted, None);
+        burn(&mut old, NaiveDate::from_ymd_opt(2025, 12, 1).unwrap()).unwrap();
+        let mut fresh = make_task(TaskState::Melted, None);
+        fresh.id = 2;
+        burn(&mut fresh, today()).unwrap();
+        let mut undated = make_task(TaskState::Evaporated, None);
+        undated.id = 3;
+        let mut active = make_task(TaskState::Melted, None);
+        active.id = 4;
+        active.depends_on = vec![1, 2];
+
+        let mut tasks = vec![old, fresh, undated, active];
+        assert_eq!(purge_evaporated(&mut tasks, today(), 30), 1);
+        let ids: Vec<u32> = tasks.iter().map(|t| t.id).collect();
+        assert_eq!(ids, [2, 3, 4]);
+        // The purged task no longer counts as a dependency
+        assert_eq!(tasks[2].depends_on, [2]);
     }
 
     // --- cool ---
@@ -192,6 +495,36 @@ mod tests {
         assert_eq!(task.thaw_date, None);
     }
 
+    #[test]
+    fn burn_records_previous_state() {
+        let mut task = make_task(TaskState::Melted, None);
+        burn(&mut task, today()).unwrap();
+        assert_eq!(task.previous_state, Some(TaskState::Melted));
+        assert_eq!(task.completed_at, Some(today()));
+        cool(&mut task).unwrap();
+        assert_eq!(task.completed_at, None);
+    }
+
+    #[test]
+    fn cool_restores_iced_with_thaw_date() {
+        let thaw = NaiveDate::from_ymd_opt(2026, 2, 1).unwrap();
+        let mut task = make_task(TaskState::Iced, Some(thaw));
+        burn(&mut task, today()).unwrap();
+        cool(&mut task).unwrap();
+        assert_eq!(task.state, TaskState::Iced);
+        assert_eq!(task.thaw_date, Some(thaw));
+        assert_eq!(task.previous_state, None);
+    }
+
+    #[test]
+    fn cool_restores_melted_after_burn() {
+        let mut task = make_task(TaskState::Melted, None);
+        burn(&mut task, today()).unwrap();
+        cool(&mut task).unwrap();
+        assert_eq!(task.state, TaskState::Melted);
+        assert_eq!(task.thaw_date, None);
+    }
+
     #[test]
     fn cool_melted_fails() {
         let mut task = make_task(TaskState::Melted, None);
@@ -203,17 +536,22 @@ mod tests {
     fn freeze_melted_to_iced() {
         let mut task = make_task(TaskState::Melted, None);
         let date = NaiveDate::from_ymd_opt(2026, 2, 1).unwrap();
-        freeze(&mut task, date).unwrap();
+        freeze(&mut task, date, today()).unwrap();
         assert_eq!(task.state, TaskState::Iced);
         assert_eq!(task.thaw_date, Some(date));
     }
 
     #[test]
     fn freeze_evaporated_to_iced() {
-        let mut task = make_task(TaskState::Evaporated, None);
+        let mut task = make_task(TaskState::Melted, None);
+        burn(&mut task, today()).unwrap();
+        task.burn_pending_until = today().succ_opt();
         let date = NaiveDate::from_ymd_opt(2026, 2, 1).unwrap();
-        freeze(&mut task, date).unwrap();
+        freeze(&mut task, date, today()).unwrap();
         assert_eq!(task.state, TaskState::Iced);
         assert_eq!(task.thaw_date, Some(date));
+        assert_eq!(task.completed_at, None);
+        assert_eq!(task.burn_pending_until, None);
+        assert_eq!(task.previous_state, None);
     }
 }
@@ -1,46 +1,101 @@
+use std::cell::{Cell, RefCell};
 use std::fs;
-use std::path::PathBuf;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
+use chrono::{NaiveDate, NaiveDateTime, SubsecRound};
+use serde::{Deserialize, Serialize};
 
-use crate::config::Config;
-use crate::models::Task;
+use crate::config::{Config, StorageBackend};
+use crate::models::{Task, TaskState};
+
+#[cfg(feature = "sqlite")]
+mod sqlite;
+#[cfg(feature = "sqlite")]
+pub use sqlite::SqliteStore;
+
+/// Persistence backend for the task list
+pub trait Store {
+    /// Load the task list. Returns an empty Vec if nothing has been saved yet.
+    fn load(&self) -> Result<Vec<Task>>;
+
+    /// Replace the stored task list
+    fn save(&self, tasks: &[Task]) -> Result<()>;
+
+    /// Get the next ID (existing maximum ID + 1, or 1 if none exist)
+    fn next_id(&self, tasks: &[Task]) -> u32 {
+        tasks.iter().map(|t| t.id).max().unwrap_or(0) + 1
+    }
+}
 
 /// Task storage using a local JSON file
-pub struct TaskStore {
+pub struct JsonStore {
     path: PathBuf,
+    /// Day given to tasks that were written without `created_at`
+    today: NaiveDate,
 }
 
-impl TaskStore {
-    /// Create a store with a path based on the configuration
-    pub fn from_config(config: &Config) -> Result<Self> {
-        let path = config.data_file_path()?;
-        Ok(Self { path })
+impl JsonStore {
+    pub fn new(path: PathBuf, today: NaiveDate) -> Self {
+        Self { path, today }
     }
 
-    /// Create a store with a specific path (for testing)
-    #[cfg(test)]
-    pub fn new_with_path(path: PathBuf) -> Self {
-        Self { path }
+    /// Parse the contents of a tasks file. Blank content is an empty task list.
+    /// Tasks missing `created_at` (usually from hand edits) get `today`, with a warning.
+    pub fn parse(content: &str, today: NaiveDate) -> Result<Vec<Task>> {
+        if content.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+        let err = match serde_json::from_str(content) {
+            Ok(tasks) => return Ok(tasks),
+            Err(err) => err,
+        };
+        // Slow path, only taken for files that fail to parse as-is
+        let Ok(mut value) = serde_json::from_str::<serde_json::Value>(content) else {
+            return Err(err.into());
+        };
+        let repaired = fill_missing_created_at(&mut value, today);
+        if repaired.is_empty() {
+            return Err(err.into());
+        }
+        let tasks = serde_json::from_value(value)?;
+        let ids: Vec<String> = repaired.iter().map(|id| format!("#{id}")).collect();
+        eprintln!(
+            "Warning: {} had no created_at; using today (saved with the next change)",
+            ids.join(", ")
+        );
+        Ok(tasks)
     }
+}
 
-    /// Load the task list. Returns an empty Vec if the file does not exist.
-    pub fn load(&self) -> Result<Vec<Task>> {
+/// Sets `created_at` to `today` on task objects that lack it. Returns the IDs of the repaired tasks.
+fn fill_missing_created_at(value: &mut serde_json::Value, today: chrono::NaiveDate) -> Vec<u64> {
+    let Some(tasks) = value.as_array_mut() else {
+        return Vec::new();
+    };
+    let mut repaired = Vec::new();
+    for task in tasks.iter_mut().filter_map(|t| t.as_object_mut()) {
+        if !task.contains_key("created_at") {
+            task.insert("created_at".to_string(), today.to_string().into());
+            repaired.push(task.get("id").and_then(|id| id.as_u64()).unwrap_or(0));
+        }
+    }
+    repaired
+}
+
+impl Store for JsonStore {
+    fn load(&self) -> Result<Vec<Task>> {
         if !self.path.exists() {
             return Ok(Vec::new());
         }
         let content = fs::read_to_string(&self.path)
             .with_context(|| format!("Failed to read {}", self.path.display()))?;
-        if content.trim().is_empty() {
-            return Ok(Vec::new());
-        }
-        let tasks: Vec<Task> = serde_json::from_str(&content)
-            .with_context(|| format!("Failed to parse {}", self.path.display()))?;
-        Ok(tasks)
+        Self::parse(&content, self.today)
+            .with_context(|| format!("Failed to parse {}", self.path.display()))
     }
 
-    /// Save the task list
-    pub fn save(&self, tasks: &[Task]) -> Result<()> {
+    fn save(&self, tasks: &[Task]) -> Result<()> {
         if let Some(parent) = self.path.parent() {
             fs::create_dir_all(parent)
                 .with_context(|| format!("Failed to create directory {}", parent.display()))?;
@@ -50,10 +105,566 @@ impl TaskStore {
             .with_context(|| format!("Failed to write {}", self.path.display()))?;
         Ok(())
     }
+}
 
-    /// Get the next ID (existing maximum ID + 1, or 1 if none exist)
-    pub fn next_id(tasks: &[Task]) -> u32 {
-        tasks.iter().map(|t| t.id).max().unwrap_or(0) + 1
+/// The task store used by commands: a backend plus the undo snapshot ring kept next to it
+pub struct TaskStore {
+    backend: Box<dyn Store>,
+    path: PathBuf,
+    /// Number of undo snapshots to keep (0 disables undo)
+    undo_depth: usize,
+    /// Days after which Evaporated tasks are deleted (None keeps them)
+    auto_purge_days: Option<u32>,
+    /// Task count above which loading warns (0 disables the warning)
+    large_store_warn: usize,
+    /// The large-store warning is printed at most once per run
+    warned_large: Cell<bool>,
+    /// Day stamped into `updated_at` of the tasks a save changes
+    today: NaiveDate,
+    /// Write tasks ordered by ID, for stable diffs of a version-controlled file
+    sort_on_save: bool,
+    /// State the automatic thaw moves tasks to
+    thaw_to: TaskState,
+    /// The tasks as last read from or written to the backend, so a save can tell what
+    /// changed without reading the file again
+    stored: RefCell<Option<Vec<Task>>>,
+}
+
+/// A copy of the task list taken before a change, used by `undo`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub taken_at: NaiveDateTime,
+    /// One-line description of the change made after the snapshot (e.g. "+1 task")
+    pub summary: String,
+    pub tasks: Vec<Task>,
+}
+
+/// What a store held at one point: the tasks, the undo ring and the length of the event log
+/// (see `TaskStore::checkpoint`)
+pub struct Checkpoint {
+    tasks: Vec<Task>,
+    snapshots: Vec<Snapshot>,
+    log_len: u64,
+}
+
+impl TaskStore {
+    /// Create a store with the backend and path based on the configuration
+    pub fn from_config(config: &Config) -> Result<Self> {
+        let path = config.data_file_path()?;
+        let backend: Box<dyn Store> = match config.storage.backend {
+            StorageBackend::Json => Box::new(JsonStore::new(path.clone(), config.today()?)),
+            #[cfg(feature = "sqlite")]
+            StorageBackend::Sqlite => Box::new(SqliteStore::open(&path)?),
+            #[cfg(not(feature = "sqlite"))]
+            StorageBackend::Sqlite => anyhow::bail!(
+                "The sqlite storage backend is not available in this build (enable the `sqlite` feature)"
+            ),
+        };
+        Self::with_backend(config, backend, path)
+    }
+
+    /// Create a store for the named list (`<name>.json` next to the data file), as read by
+    /// `list --all-lists`. Named lists are always JSON files.
+    pub fn for_list(config: &Config, name: &str) -> Result<Self> {
+        let data_file = config.data_file_path()?;
+        let dir = data_file.parent().unwrap_or(Path::new("."));
+        let path = dir.join(format!("{name}.json"));
+        if path == data_file {
+            return Self::from_config(config);
+        }
+        if !path.is_file() {
+            anyhow::bail!("List '{name}' not found ({} does not exist)", path.display());
+        }
+        let backend = Box::new(JsonStore::new(path.clone(), config.today()?));
+        Self::with_backend(config, backend, path)
+    }
+
+    fn with_backend(config: &Config, backend: Box<dyn Store>, path: PathBuf) -> Result<Self> {
+        Ok(Self {
+            backend,
+            path,
+            undo_depth: config.defaults.undo_depth,
+            auto_purge_days: config.defaults.auto_purge_days,
+            large_store_warn: config.defaults.large_store_warn,
+            warned_large: Cell::new(false),
+            today: config.today()?,
+            sort_on_save: config.storage.sort_on_save,
+            thaw_to: config.defaults.thaw_to.state(),
+            stored: RefCell::new(None),
+        })
+    }
+
+    /// Create a JSON store with a specific path and default settings, independent of the
+    /// config (used by `validate` and tests). `today` is the day stamped into changed tasks.
+    pub fn new_with_path(path: PathBuf, today: NaiveDate) -> Self {
+        Self {
+            backend: Box::new(JsonStore::new(path.clone(), today)),
+            path,
+            undo_depth: 0,
+            auto_purge_days: None,
+            large_store_warn: 0,
+            warned_large: Cell::new(false),
+            today,
+            sort_on_save: false,
+            thaw_to: TaskState::Melting,
+            stored: RefCell::new(None),
+        }
+    }
+
+    /// Keep up to `depth` undo snapshots
+    #[cfg(test)]
+    pub fn with_undo_depth(mut self, depth: usize) -> Self {
+        self.undo_depth = depth;
+        self
+    }
+
+    /// Write tasks ordered by ID
+    #[cfg(test)]
+    pub fn with_sort_on_save(mut self) -> Self {
+        self.sort_on_save = true;
+        self
+    }
+
+    /// Load the task list. Returns an empty Vec if nothing has been saved yet.
+    pub fn load(&self) -> Result<Vec<Task>> {
+        let tasks = self.backend.load()?;
+        let limit = self.large_store_warn;
+        if limit > 0 && tasks.len() > limit && !self.warned_large.replace(true) {
+            eprintln!(
+                "Warning: {} tasks stored (more than defaults.large_store_warn = {limit}); \
+                 consider defaults.auto_purge_days or the sqlite backend",
+                tasks.len()
+            );
+        }
+        *self.stored.borrow_mut() = Some(tasks.clone());
+        Ok(tasks)
+    }
+
+    /// The tasks currently in the backend: what was last loaded or written, read again only
+    /// when this store has not seen them yet
+    fn take_stored(&self) -> Result<Vec<Task>> {
+        match self.stored.take() {
+            Some(tasks) => Ok(tasks),
+            None => self.backend.load(),
+        }
+    }
+
+    /// Days after which Evaporated tasks are purged, if enabled in the config
+    pub fn auto_purge_days(&self) -> Option<u32> {
+        self.auto_purge_days
+    }
+
+    /// State the automatic thaw moves tasks to (`defaults.thaw_to`, or `--thaw-to`)
+    pub fn thaw_to(&self) -> TaskState {
+        self.thaw_to
+    }
+
+    /// Load every task list in the directory of this store, sorted by list name: this store's
+    /// own list, read through its backend (which may be sqlite), and the other `*.json` files.
+    /// The list name is the file name without its extension. JSON files that are not task
+    /// lists are skipped with a warning.
+    pub fn load_all_lists(&self) -> Result<Vec<(String, Vec<Task>)>> {
+        let Some(dir) = self.path.parent() else {
+            return Ok(Vec::new());
+        };
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+        let own = self.path.file_stem().map(|s| s.to_string_lossy().into_owned());
+        let mut lists = Vec::new();
+        if let Some(own) = &own
+            && self.path.is_file()
+        {
+            lists.push((own.clone(), self.backend.load()?));
+        }
+        for entry in fs::read_dir(dir)
+            .with_context(|| format!("Failed to read directory {}", dir.display()))?
+        {
+            let path = entry?.path();
+            if path.extension().is_none_or(|ext| ext != "json") || !path.is_file() {
+                continue;
+            }
+            let Some(name) = path.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            // The own list was read above (a tasks.json next to a sqlite tasks.db is not a list)
+            if name.starts_with('.') || own.as_deref() == Some(name) {
+                continue;
+            }
+            let name = name.to_string();
+            match JsonStore::new(path.clone(), self.today).load() {
+                Ok(tasks) => lists.push((name, tasks)),
+                Err(err) => eprintln!("Warning: skipping {}: {err:#}", path.display()),
+            }
+        }
+        lists.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(lists)
+    }
+
+    /// Path of the underlying data file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Save the task list, recording an undo snapshot of the previous contents if anything changed.
+    /// An unchanged list is not rewritten at all.
+    pub fn save(&self, tasks: &[Task]) -> Result<()> {
+        let previous = self.take_stored()?;
+        let tasks = &self.prepare(&previous, tasks, Some(self.today));
+        if previous == *tasks {
+            *self.stored.borrow_mut() = Some(previous);
+            return Ok(());
+        }
+        self.record_snapshot(&previous, describe_change(&previous, tasks))?;
+        self.write(&previous, tasks, None)
+    }
+
+    /// Save after task `old` was given the ID `new` (and references to it were updated).
+    /// The log records the renumbering instead of a deletion and an addition; `task_history`
+    /// follows it back to the events logged under `old`.
+    pub fn save_renumbered(&self, tasks: &[Task], old: u32, new: u32) -> Result<()> {
+        let previous = self.take_stored()?;
+        self.record_snapshot(&previous, format!("#{old} → #{new}"))?;
+
+        let renamed: Vec<Task> = previous
+            .into_iter()
+            .map(|mut t| {
+                if t.id == old {
+                    t.id = new;
+                }
+                t
+            })
+            .collect();
+        let tasks = self.prepare(&renamed, tasks, Some(self.today));
+        self.write(&renamed, &tasks, None)?;
+        self.append_events(&[Event {
+            at: chrono::Local::now().naive_local().trunc_subsecs(0),
+            id: new,
+            event: format!("{RENUMBERED_FROM}{old}"),
+        }])
+    }
+
+    /// The list as it will be written: `updated_at` stamped (see `stamp_updated`),
+    /// and ordered by ID when `storage.sort_on_save` is set
+    fn prepare(&self, previous: &[Task], tasks: &[Task], today: Option<NaiveDate>) -> Vec<Task> {
+        let mut tasks = stamp_updated(previous, tasks, today);
+        if self.sort_on_save {
+            tasks.sort_by_key(|t| t.id);
+        }
+        tasks
+    }
+
+    /// Pushes `previous` onto the undo ring (no-op when undo is disabled)
+    fn record_snapshot(&self, previous: &[Task], summary: String) -> Result<()> {
+        if self.undo_depth == 0 {
+            return Ok(());
+        }
+        let mut ring = self.load_snapshots()?;
+        ring.push(Snapshot {
+            taken_at: chrono::Local::now().naive_local(),
+            summary,
+            tasks: previous.to_vec(),
+        });
+        let excess = ring.len().saturating_sub(self.undo_depth);
+        ring.drain(..excess);
+        self.save_snapshots(&ring)
+    }
+
+    /// Remember the current tasks, undo snapshots and event log, so a save can be taken back
+    /// entirely with `restore`
+    pub fn checkpoint(&self) -> Result<Checkpoint> {
+        let tasks = self.take_stored()?;
+        *self.stored.borrow_mut() = Some(tasks.clone());
+        let log_len = match fs::metadata(self.log_path()) {
+            Ok(meta) => meta.len(),
+            Err(_) => 0,
+        };
+        Ok(Checkpoint {
+            tasks,
+            snapshots: self.load_snapshots()?,
+            log_len,
+        })
+    }
+
+    /// Return to `checkpoint`: the tasks are written back, and the undo snapshots and log
+    /// events recorded since are dropped, as if the saves in between never happened
+    pub fn restore(&self, checkpoint: Checkpoint) -> Result<()> {
+        self.backend.save(&checkpoint.tasks)?;
+        *self.stored.borrow_mut() = Some(checkpoint.tasks);
+        if self.undo_depth > 0 {
+            self.save_snapshots(&checkpoint.snapshots)?;
+        }
+        let path = self.log_path();
+        if path.exists() {
+            fs::OpenOptions::new()
+                .write(true)
+                .open(&path)
+                .and_then(|file| file.set_len(checkpoint.log_len))
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+        }
+        Ok(())
+    }
+
+    /// Save the task list without recording an undo snapshot.
+    /// Used for automatic changes (such as auto_warm), which do not count as updates.
+    pub fn save_untracked(&self, tasks: &[Task]) -> Result<()> {
+        let previous = self.take_stored()?;
+        self.write(&previous, &self.prepare(&previous, tasks, None), None)
+    }
+
+    /// Writes `tasks` to the backend and appends what changed since `previous` to the event log.
+    /// `label` replaces the derived event names (used by undo).
+    fn write(&self, previous: &[Task], tasks: &[Task], label: Option<&str>) -> Result<()> {
+        self.backend.save(tasks)?;
+        *self.stored.borrow_mut() = Some(tasks.to_vec());
+        let at = chrono::Local::now().naive_local().trunc_subsecs(0);
+        let events: Vec<Event> = task_events(previous, tasks)
+            .into_iter()
+            .map(|(id, event)| Event {
+                at,
+                id,
+                event: label.unwrap_or(event).to_string(),
+            })
+            .collect();
+        self.append_events(&events)
+    }
+
+    /// Path of the append-only event log (next to the tasks file, e.g. tasks.json.log)
+    fn log_path(&self) -> PathBuf {
+        let mut path = self.path.clone().into_os_string();
+        path.push(".log");
+        PathBuf::from(path)
+    }
+
+    fn append_events(&self, events: &[Event]) -> Result<()> {
+        if events.is_empty() {
+            return Ok(());
+        }
+        let path = self.log_path();
+        let mut lines = String::new();
+        for event in events {
+            lines.push_str(&serde_json::to_string(event)?);
+            lines.push('\n');
+        }
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("Failed to open {}", path.display()))?;
+        file.write_all(lines.as_bytes())
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Load the event log, oldest first
+    pub fn load_events(&self) -> Result<Vec<Event>> {
+        let path = self.log_path();
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                serde_json::from_str(line)
+                    .with_context(|| format!("Failed to parse {}", path.display()))
+            })
+            .collect()
+    }
+
+    /// The logged events of the task that now has the ID `id`, oldest first
+    /// (see `history_of`)
+    pub fn task_history(&self, id: u32) -> Result<Vec<Event>> {
+        let events = self.load_events()?;
+        Ok(history_of(&events, id).into_iter().cloned().collect())
+    }
+
+    /// Path of the undo snapshot ring (next to the tasks file, e.g. tasks.json.undo)
+    fn undo_path(&self) -> PathBuf {
+        let mut path = self.path.clone().into_os_string();
+        path.push(".undo");
+        PathBuf::from(path)
+    }
+
+    /// Load the undo snapshots, oldest first
+    pub fn load_snapshots(&self) -> Result<Vec<Snapshot>> {
+        let path = self.undo_path();
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    fn save_snapshots(&self, ring: &[Snapshot]) -> Result<()> {
+        let path = self.undo_path();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory {}", parent.display()))?;
+        }
+        let content = serde_json::to_string(ring)?;
+        fs::write(&path, content).with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Revert the last `steps` changes: restore the snapshot taken before the oldest of them
+    /// and remove those snapshots from the ring. Returns the reverted snapshots, newest first;
+    /// empty if there is nothing to undo. Fails if fewer than `steps` changes can be undone.
+    pub fn undo(&self, steps: usize) -> Result<Vec<Snapshot>> {
+        let mut ring = self.load_snapshots()?;
+        if ring.is_empty() {
+            return Ok(Vec::new());
+        }
+        check_undo_steps(ring.len(), steps)?;
+        let mut undone = ring.split_off(ring.len() - steps);
+        undone.reverse();
+        let oldest = undone.last().expect("steps is at least 1");
+        let current = self.take_stored()?;
+        let restored = self.prepare(&current, &oldest.tasks, Some(self.today));
+        self.write(&current, &restored, Some("undone"))?;
+        self.save_snapshots(&ring)?;
+        Ok(undone)
+    }
+
+    /// Get the next ID for a new task
+    pub fn next_id(&self, tasks: &[Task]) -> u32 {
+        self.backend.next_id(tasks)
+    }
+}
+
+/// Fails if `steps` changes cannot be undone with `kept` undo snapshots
+pub fn check_undo_steps(kept: usize, steps: usize) -> Result<()> {
+    if kept < steps {
+        let noun = if kept == 1 { "step" } else { "steps" };
+        anyhow::bail!("Cannot undo {steps} changes: only {kept} undo {noun} kept");
+    }
+    Ok(())
+}
+
+/// Prefix of the event `set-id` logs under the new ID, followed by the old one
+const RENUMBERED_FROM: &str = "renumbered from #";
+
+/// The events in `events` that belong to the task last known as `id`. An "added" event starts
+/// the history afresh (the ID may have belonged to a deleted task), and a renumbering takes
+/// over the history of the old ID up to that point.
+fn history_of(events: &[Event], id: u32) -> Vec<&Event> {
+    let mut history = Vec::new();
+    for (i, event) in events.iter().enumerate().filter(|(_, e)| e.id == id) {
+        if event.event == "added" {
+            history.clear();
+        }
+        if let Some(old) = event.event.strip_prefix(RENUMBERED_FROM).and_then(|n| n.parse().ok()) {
+            history = history_of(&events[..i], old);
+        }
+        history.push(event);
+    }
+    history
+}
+
+/// One line of the event log: what happened to which task, and when
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Event {
+    pub at: NaiveDateTime,
+    pub id: u32,
+    /// e.g. "added", "burned", "edited"
+    pub event: String,
+}
+
+/// Names the per-task events that turn `before` into `after`, e.g. (3, "burned")
+pub fn task_events(before: &[Task], after: &[Task]) -> Vec<(u32, &'static str)> {
+    let mut events = Vec::new();
+    for task in after {
+        let Some(old) = before.iter().find(|b| b.id == task.id) else {
+            events.push((task.id, "added"));
+            continue;
+        };
+        let event = match (old.state, task.state) {
+            (from, to) if from == to => {
+                if old == task {
+                    continue;
+                }
+                "edited"
+            }
+            (TaskState::Evaporated, _) => "cooled",
+            (_, TaskState::Evaporated) => "burned",
+            (_, TaskState::Iced) => "frozen",
+            (_, TaskState::Melting) => "thawed",
+            (_, TaskState::Melted) => "warmed",
+        };
+        events.push((task.id, event));
+    }
+    for old in before {
+        if !after.iter().any(|t| t.id == old.id) {
+            events.push((old.id, "deleted"));
+        }
+    }
+    events
+}
+
+/// Returns `tasks` with `updated_at` brought up to date: tasks that are unchanged apart from it keep
+/// the day stored in `previous`, while new and changed tasks get `today` (when given).
+fn stamp_updated(previous: &[Task], tasks: &[Task], today: Option<NaiveDate>) -> Vec<Task> {
+    tasks
+        .iter()
+        .map(|task| {
+            let mut task = task.clone();
+            let old = previous.iter().find(|p| p.id == task.id);
+            match old {
+                Some(old) if Task { updated_at: old.updated_at, ..task.clone() } == *old => {
+                    task.updated_at = old.updated_at;
+                }
+                _ => {
+                    if let Some(today) = today {
+                        task.updated_at = Some(today);
+                    }
+                }
+            }
+            task
+        })
+        .collect()
+}
+
+/// Summarizes the difference between two task lists in one line, e.g. "+1 task, state change #3"
+pub fn describe_change(before: &[Task], after: &[Task]) -> String {
+    let added = after
+        .iter()
+        .filter(|t| !before.iter().any(|b| b.id == t.id))
+        .count();
+    let removed = before
+        .iter()
+        .filter(|b| !after.iter().any(|t| t.id == b.id))
+        .count();
+    let mut state_changes = Vec::new();
+    let mut edits = Vec::new();
+    for task in after {
+        if let Some(old) = before.iter().find(|b| b.id == task.id) {
+            if old.state != task.state {
+                state_changes.push(format!("#{}", task.id));
+            } else if old != task {
+                edits.push(format!("#{}", task.id));
+            }
+        }
+    }
+
+    let plural = |n: usize| if n == 1 { "task" } else { "tasks" };
+    let mut parts = Vec::new();
+    if added > 0 {
+        parts.push(format!("+{added} {}", plural(added)));
+    }
+    if removed > 0 {
+        parts.push(format!("-{removed} {}", plural(removed)));
+    }
+    if !state_changes.is_empty() {
+        parts.push(format!("state change {}", state_changes.join(", ")));
+    }
+    if !edits.is_empty() {
+        parts.push(format!("edit {}", edits.join(", ")));
+    }
+    if parts.is_empty() {
+        "reordered".to_string()
+    } else {
+        parts.join(", ")
     }
 }
 
@@ -63,21 +674,150 @@ mod tests {
     use crate::models::{TaskState, Task};
     use chrono::NaiveDate;
 
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 3, 1).unwrap()
+    }
+
     fn sample_task(id: u32) -> Task {
         Task {
             id,
             title: format!("Task {id}"),
-            description: String::new(),
-            state: TaskState::Melted,
-            thaw_date: None,
-            due_date: None,
-            created_at: NaiveDate::from_ymd_opt(2026, 1, 1).unwrap(),
+            ..Task::default()
         }
     }
 
+    #[test]
+    fn task_events_name_each_transition() {
+        let before = vec![sample_task(1), sample_task(2), sample_task(3)];
+        let mut after = before.clone();
+        after[0].state = TaskState::Evaporated;
+        after[1].title = "Renamed".to_string();
+        after.remove(2);
+        after.push(sample_task(4));
+        assert_eq!(
+            task_events(&before, &after),
+            [(1, "burned"), (2, "edited"), (4, "added"), (3, "deleted")]
+        );
+        assert_eq!(task_events(&after, &before)[0], (1, "cooled"));
+    }
+
+    #[test]
+    fn restore_drops_the_snapshot_and_events_of_later_saves() {
+        let dir = tempfile::tempdir().unwrap();
+        let store =
+            TaskStore::new_with_path(dir.path().join("tasks.json"), today()).with_undo_depth(5);
+        store.save(&[sample_task(1)]).unwrap();
+        let checkpoint = store.checkpoint().unwrap();
+
+        store.save(&[sample_task(1), sample_task(2)]).unwrap();
+        assert_eq!(store.load_snapshots().unwrap().len(), 2);
+        store.restore(checkpoint).unwrap();
+
+        let ids: Vec<u32> = store.load().unwrap().iter().map(|t| t.id).collect();
+        assert_eq!(ids, [1]);
+        assert_eq!(store.load_snapshots().unwrap().len(), 1);
+        let events: Vec<u32> = store.load_events().unwrap().iter().map(|e| e.id).collect();
+        assert_eq!(events, [1]);
+    }
+
+    #[test]
+    fn sort_on_save_writes_tasks_by_id() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tasks.json");
+        let store = TaskStore::new_with_path(path.clone(), today()).with_sort_on_save();
+        store.save(&[sample_task(3), sample_task(1), sample_task(2)]).unwrap();
+        let ids: Vec<u32> = store.load().unwrap().iter().map(|t| t.id).collect();
+        assert_eq!(ids, [1, 2, 3]);
+
+        let unsorted = TaskStore::new_with_path(dir.path().join("other.json"), today());
+        unsorted.save(&[sample_task(3), sample_task(1)]).unwrap();
+        let ids: Vec<u32> = unsorted.load().unwrap().iter().map(|t| t.id).collect();
+        assert_eq!(ids, [3, 1]);
+    }
+
+    #[test]
+    fn stamp_updated_marks_only_changed_tasks() {
+        let day = |d| NaiveDate::from_ymd_opt(2026, 1, d).unwrap();
+        let mut before = vec![sample_task(1), sample_task(2)];
+        before[0].updated_at = Some(day(1));
+        before[1].updated_at = Some(day(1));
+        let mut after = before.clone();
+        after[0].updated_at = None;
+        after[1].title = "Renamed".to_string();
+        after.push(sample_task(3));
+
+        let stamped = stamp_updated(&before, &after, Some(day(5)));
+        let days: Vec<_> = stamped.iter().map(|t| t.updated_at).collect();
+        assert_eq!(days, [Some(day(1)), Some(day(5)), Some(day(5))]);
+        // Automatic saves keep whatever the tasks carry
+        assert_eq!(stamp_updated(&before, &after, None)[1].updated_at, Some(day(1)));
+    }
+
+    #[test]
+    fn saves_append_to_the_event_log() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = TaskStore::new_with_path(dir.path().join("tasks.json"), today());
+        store.save(&[sample_task(1)]).unwrap();
+        let mut task = sample_task(1);
+        task.state = TaskState::Iced;
+        store.save_untracked(&[task]).unwrap();
+
+        let events: Vec<(u32, String)> = store
+            .load_events()
+            .unwrap()
+            .into_iter()
+            .map(|e| (e.id, e.event))
+            .collect();
+        assert_eq!(events, [(1, "added".to_string()), (1, "frozen".to_string())]);
+    }
+
+    #[test]
+    fn history_follows_renumbering_but_not_reused_ids() {
+        let dir = tempfile::tempdir().unwrap();
+        let store =
+            TaskStore::new_with_path(dir.path().join("tasks.json"), today()).with_undo_depth(5);
+        let events = |id| -> Vec<String> {
+            store.task_history(id).unwrap().into_iter().map(|e| e.event).collect()
+        };
+        store.save(&[sample_task(1), sample_task(2)]).unwrap();
+        store.save(&[sample_task(1)]).unwrap();
+        store.save(&[sample_task(1), sample_task(2)]).unwrap();
+        // Task 2 was deleted and the ID reused
+        assert_eq!(events(2), ["added"]);
+        // Renumbering onto the ID of a deleted task leaves that task's events out
+        store.save(&[sample_task(1), sample_task(2), sample_task(5)]).unwrap();
+        store.save(&[sample_task(1), sample_task(2)]).unwrap();
+        let mut renumbered = sample_task(1);
+        renumbered.id = 5;
+        store.save_renumbered(&[renumbered, sample_task(2)], 1, 5).unwrap();
+        assert_eq!(events(5), ["added", "renumbered from #1"]);
+
+        store.undo(1).unwrap();
+        assert_eq!(events(1), ["added", "undone"]);
+    }
+
+    #[test]
+    fn missing_created_at_is_filled_in() {
+        let content = r#"[
+            {"id": 1, "title": "Hand-written", "description": "", "state": "melted", "thaw_date": null, "due_date": null},
+            {"id": 2, "title": "Complete", "description": "", "state": "iced", "thaw_date": "2026-02-01", "due_date": null, "created_at": "2026-01-01"}
+        ]"#;
+        let tasks = JsonStore::parse(content, today()).unwrap();
+        assert_eq!(tasks.len(), 2);
+        assert_eq!(tasks[1].created_at, NaiveDate::from_ymd_opt(2026, 1, 1).unwrap());
+
+        let mut value: serde_json::Value = serde_json::from_str(content).unwrap();
+        assert_eq!(fill_missing_created_at(&mut value, today()), [1]);
+        assert_eq!(value[0]["created_at"], "2026-03-01");
+
+        // Other problems still fail
+        assert!(JsonStore::parse(r#"[{"id": 1}]"#, today()).is_err());
+    }
+
     #[test]
     fn load_nonexistent_file() {
-        let store = TaskStore::new_with_path(PathBuf::from("/tmp/kelvin_test_nonexistent.json"));
+        let store =
+            TaskStore::new_with_path(PathBuf::from("/tmp/kelvin_test_nonexistent.json"), today());
         let tasks = store.load().unwrap();
         assert!(tasks.is_empty());
     }
@@ -86,7 +826,7 @@ mod tests {
     fn save_and_load_roundtrip() {
         let dir = tempfile::tempdir().unwrap();
         let path = dir.path().join("tasks.json");
-        let store = TaskStore::new_with_path(path.clone());
+        let store = TaskStore::new_with_path(path.clone(), today());
 
         let tasks = vec![sample_task(1), sample_task(2)];
         store.save(&tasks).unwrap();
@@ -97,14 +837,123 @@ mod tests {
         assert_eq!(loaded[1].id, 2);
     }
 
+    #[test]
+    fn parse_blank_and_invalid_content() {
+        assert!(JsonStore::parse("  \n", today()).unwrap().is_empty());
+        assert!(JsonStore::parse("[{\"id\": 1", today()).is_err());
+    }
+
+    #[test]
+    fn load_all_lists_reads_every_json_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let work = TaskStore::new_with_path(dir.path().join("work.json"), today());
+        work.save(&[sample_task(1)]).unwrap();
+        let home = TaskStore::new_with_path(dir.path().join("home.json"), today());
+        home.save(&[sample_task(1), sample_task(2)]).unwrap();
+        fs::write(dir.path().join("notes.txt"), "not a list").unwrap();
+        fs::write(dir.path().join("package.json"), r#"{"name": "not a list"}"#).unwrap();
+
+        let lists = work.load_all_lists().unwrap();
+        let names: Vec<&str> = lists.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, ["home", "work"]);
+        assert_eq!(lists[0].1.len(), 2);
+        assert_eq!(lists[1].1.len(), 1);
+    }
+
+    #[test]
+    fn save_records_undo_snapshots_up_to_depth() {
+        let dir = tempfile::tempdir().unwrap();
+        let store =
+            TaskStore::new_with_path(dir.path().join("tasks.json"), today()).with_undo_depth(2);
+
+        store.save(&[sample_task(1)]).unwrap();
+        store.save(&[sample_task(1), sample_task(2)]).unwrap();
+        // Saving identical contents does not add a snapshot (or rewrite the file)
+        let modified = fs::metadata(store.path()).unwrap().modified().unwrap();
+        store.save(&[sample_task(1), sample_task(2)]).unwrap();
+        assert_eq!(fs::metadata(store.path()).unwrap().modified().unwrap(), modified);
+        store.save(&[sample_task(1), sample_task(2), sample_task(3)]).unwrap();
+
+        let ring = store.load_snapshots().unwrap();
+        assert_eq!(ring.len(), 2);
+        assert_eq!(ring[0].tasks.len(), 1);
+        assert_eq!(ring[1].tasks.len(), 2);
+        assert_eq!(ring[1].summary, "+1 task");
+    }
+
+    #[test]
+    fn save_compares_against_the_loaded_tasks_without_rereading() {
+        let dir = tempfile::tempdir().unwrap();
+        let store =
+            TaskStore::new_with_path(dir.path().join("tasks.json"), today()).with_undo_depth(2);
+        store.save(&[sample_task(1)]).unwrap();
+        let tasks = store.load().unwrap();
+        // Unreadable now, but neither save has to parse the file again
+        fs::write(store.path(), "not json").unwrap();
+        store.save(&tasks).unwrap();
+        store.save(&[sample_task(1), sample_task(2)]).unwrap();
+        assert_eq!(store.load().unwrap().len(), 2);
+        assert_eq!(store.load_snapshots().unwrap()[1].tasks.len(), 1);
+    }
+
+    #[test]
+    fn undo_restores_latest_snapshot() {
+        let dir = tempfile::tempdir().unwrap();
+        let store =
+            TaskStore::new_with_path(dir.path().join("tasks.json"), today()).with_undo_depth(5);
+        store.save(&[sample_task(1)]).unwrap();
+        store.save(&[sample_task(1), sample_task(2)]).unwrap();
+
+        let undone = store.undo(1).unwrap();
+        assert_eq!(undone[0].summary, "+1 task");
+        assert_eq!(store.load().unwrap().len(), 1);
+        assert_eq!(store.load_snapshots().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn undo_several_steps_at_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let store =
+            TaskStore::new_with_path(dir.path().join("tasks.json"), today()).with_undo_depth(5);
+        store.save(&[sample_task(1)]).unwrap();
+        store.save(&[sample_task(1), sample_task(2)]).unwrap();
+        store.save(&[sample_task(2)]).unwrap();
+
+        assert!(store.undo(4).is_err());
+        assert_eq!(store.load().unwrap().len(), 1);
+
+        let undone = store.undo(2).unwrap();
+        let summaries: Vec<&str> = undone.iter().map(|s| s.summary.as_str()).collect();
+        assert_eq!(summaries, ["-1 task", "+1 task"]);
+        let ids: Vec<u32> = store.load().unwrap().iter().map(|t| t.id).collect();
+        assert_eq!(ids, [1]);
+        assert_eq!(store.load_snapshots().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn describe_change_summaries() {
+        let before = vec![sample_task(1), sample_task(2)];
+        let mut after = vec![sample_task(1), sample_task(3)];
+        after[0].state = TaskState::Evaporated;
+        assert_eq!(describe_change(&before, &after), "+1 task, -1 task, state change #1");
+
+        let mut edited = before.clone();
+        edited[1].title = "Renamed".to_string();
+        assert_eq!(describe_change(&before, &edited), "edit #2");
+    }
+
     #[test]
     fn next_id_empty() {
-        assert_eq!(TaskStore::next_id(&[]), 1);
+        let store =
+            TaskStore::new_with_path(PathBuf::from("/tmp/kelvin_test_nonexistent.json"), today());
+        assert_eq!(store.next_id(&[]), 1);
     }
 
     #[test]
     fn next_id_with_tasks() {
+        let store =
+            TaskStore::new_with_path(PathBuf::from("/tmp/kelvin_test_nonexistent.json"), today());
         let tasks = vec![sample_task(5), sample_task(3)];
-        assert_eq!(TaskStore::next_id(&tasks), 6);
+        assert_eq!(store.next_id(&tasks), 6);
     }
 }
@@ -0,0 +1,93 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection};
+
+use super::Store;
+use crate::models::Task;
+
+/// Task storage in a SQLite database.
+/// Each task is stored as a JSON document keyed by ID, so new fields need no schema migration.
+pub struct SqliteStore {
+    conn: Connection,
+}
+
+impl SqliteStore {
+    /// Open (or create) the database at `path`
+    pub fn open(path: &Path) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory {}", parent.display()))?;
+        }
+        let conn = Connection::open(path)
+            .with_context(|| format!("Failed to open {}", path.display()))?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS tasks (
+                id INTEGER PRIMARY KEY,
+                position INTEGER NOT NULL,
+                data TEXT NOT NULL
+            )",
+        )?;
+        Ok(Self { conn })
+    }
+}
+
+impl Store for SqliteStore {
+    fn load(&self) -> Result<Vec<Task>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT data FROM tasks ORDER BY position")?;
+        let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+        let mut tasks = Vec::new();
+        for data in rows {
+            tasks.push(serde_json::from_str(&data?)?);
+        }
+        Ok(tasks)
+    }
+
+    fn save(&self, tasks: &[Task]) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute("DELETE FROM tasks", [])?;
+        {
+            let mut stmt =
+                tx.prepare("INSERT INTO tasks (id, position, data) VALUES (?1, ?2, ?3)")?;
+            for (position, task) in tasks.iter().enumerate() {
+                stmt.execute(params![
+                    task.id,
+                    position as i64,
+                    serde_json::to_string(task)?
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_task(id: u32) -> Task {
+        Task {
+            id,
+            title: format!("Task {id}"),
+            ..Task::default()
+        }
+    }
+
+    #[test]
+    fn save_and_load_roundtrip_keeps_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SqliteStore::open(&dir.path().join("tasks.db")).unwrap();
+        assert!(store.load().unwrap().is_empty());
+
+        store.save(&[sample_task(3), sample_task(1)]).unwrap();
+        let ids: Vec<u32> = store.load().unwrap().iter().map(|t| t.id).collect();
+        assert_eq!(ids, [3, 1]);
+
+        store.save(&[sample_task(1)]).unwrap();
+        assert_eq!(store.load().unwrap().len(), 1);
+    }
+}
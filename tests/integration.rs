@@ -1,3 +1,4 @@
+use std::path::Path;
 use std::process::Command;
 
 /// Helper: Construct a command for the kelvin binary (uses a test data directory)
@@ -8,6 +9,18 @@ fn kelvin_cmd() -> Command {
     cmd
 }
 
+/// Helper: Construct a command whose HOME points at the given test directory
+fn kelvin_in(dir: &Path) -> Command {
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_kelvin"));
+    cmd.env("HOME", dir).env("XDG_CONFIG_HOME", dir.join(".config"));
+    cmd
+}
+
+/// Helper: Read the raw tasks.json written under the test directory
+fn read_tasks_json(dir: &Path) -> String {
+    std::fs::read_to_string(dir.join(".config").join("kelvin").join("tasks.json")).unwrap()
+}
+
 #[test]
 fn help_displays() {
     let output = kelvin_cmd()
@@ -221,6 +234,8 @@ fn freeze_and_warm() {
     assert!(output.status.success());
     let stdout = String::from_utf8_lossy(&output.stdout);
     assert!(stdout.contains("Froze"));
+    assert!(stdout.contains("Hidden from list until"));
+    assert!(stdout.contains("list --iced"));
 
     // warm back to Melted
     let output = Command::new(env!("CARGO_BIN_EXE_kelvin"))
@@ -267,3 +282,2467 @@ fn edit_task_title() {
     assert!(stdout.contains("New title"));
     assert!(!stdout.contains("Old title"));
 }
+
+#[test]
+fn failed_command_still_persists_auto_warm() {
+    let dir = tempfile::tempdir().unwrap();
+
+    // An Iced task whose thaw date has already passed
+    let output = kelvin_in(dir.path())
+        .args(["add", "Thaw me", "-d", "2020-01-01"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(read_tasks_json(dir.path()).contains("\"iced\""));
+
+    // Editing a missing task fails...
+    let output = kelvin_in(dir.path())
+        .args(["edit", "99", "-t", "Nope"])
+        .output()
+        .expect("Failed to execute kelvin edit");
+    assert!(!output.status.success());
+
+    // ...but the thaw that happened along the way is saved
+    let json = read_tasks_json(dir.path());
+    assert!(json.contains("\"melting\""));
+    assert!(!json.contains("\"iced\""));
+}
+
+#[test]
+fn graph_shows_dependency_tree_and_dot() {
+    let dir = tempfile::tempdir().unwrap();
+    kelvin_in(dir.path()).args(["add", "Setup"]).output().unwrap();
+    kelvin_in(dir.path())
+        .args(["add", "Deploy", "--depends", "1"])
+        .output()
+        .unwrap();
+
+    let output = kelvin_in(dir.path())
+        .args(["graph"])
+        .output()
+        .expect("Failed to execute kelvin graph");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("#2 Deploy"));
+    assert!(stdout.contains("  #1 Setup"));
+
+    let output = kelvin_in(dir.path())
+        .args(["graph", "--dot"])
+        .output()
+        .expect("Failed to execute kelvin graph --dot");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with("digraph kelvin {"));
+    assert!(stdout.contains("\"2\" -> \"1\";"));
+}
+
+#[test]
+fn edit_rejects_dependency_cycle() {
+    let dir = tempfile::tempdir().unwrap();
+    kelvin_in(dir.path()).args(["add", "First"]).output().unwrap();
+    kelvin_in(dir.path())
+        .args(["add", "Second", "--depends", "1"])
+        .output()
+        .unwrap();
+
+    let output = kelvin_in(dir.path())
+        .args(["edit", "1", "--depends", "2"])
+        .output()
+        .expect("Failed to execute kelvin edit");
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("cycle"));
+}
+
+#[test]
+fn list_limit_reports_hidden_tasks() {
+    let dir = tempfile::tempdir().unwrap();
+    for title in ["One", "Two", "Three"] {
+        kelvin_in(dir.path()).args(["add", title]).output().unwrap();
+    }
+
+    let output = kelvin_in(dir.path())
+        .args(["list", "--limit", "1"])
+        .output()
+        .expect("Failed to execute kelvin list --limit");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("One"));
+    assert!(!stdout.contains("Two"));
+    assert!(stdout.contains("... and 2 more (use --all or raise --limit)"));
+
+    // No notice when everything fits
+    let output = kelvin_in(dir.path())
+        .args(["list", "--limit", "3"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!stdout.contains("more"));
+}
+
+/// Helper: Write an executable shell script to use as $EDITOR
+#[cfg(unix)]
+fn editor_script(dir: &Path, body: &str) -> std::path::PathBuf {
+    use std::os::unix::fs::PermissionsExt;
+    let path = dir.join("editor.sh");
+    std::fs::write(&path, format!("#!/bin/sh\n{body}\n")).unwrap();
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    path
+}
+
+#[test]
+fn open_print_shows_data_file_path() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = kelvin_in(dir.path())
+        .args(["open", "--print"])
+        .output()
+        .expect("Failed to execute kelvin open --print");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.trim().ends_with("tasks.json"));
+    assert!(Path::new(stdout.trim()).is_absolute());
+}
+
+#[cfg(unix)]
+#[test]
+fn open_rejects_invalid_json_without_touching_store() {
+    let dir = tempfile::tempdir().unwrap();
+    kelvin_in(dir.path()).args(["add", "Keep me"]).output().unwrap();
+    let before = read_tasks_json(dir.path());
+
+    let editor = editor_script(dir.path(), "echo '[{broken' > \"$1\"");
+    let output = kelvin_in(dir.path())
+        .env_remove("VISUAL")
+        .env("EDITOR", &editor)
+        .args(["open"])
+        .output()
+        .expect("Failed to execute kelvin open");
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("not a valid task list"));
+    assert_eq!(read_tasks_json(dir.path()), before);
+}
+
+#[cfg(unix)]
+#[test]
+fn open_applies_valid_edits() {
+    let dir = tempfile::tempdir().unwrap();
+    kelvin_in(dir.path()).args(["add", "Old title"]).output().unwrap();
+
+    let editor = editor_script(dir.path(), "sed -i.bak 's/Old title/New title/' \"$1\"");
+    let output = kelvin_in(dir.path())
+        .env_remove("VISUAL")
+        .env("EDITOR", &editor)
+        .args(["open"])
+        .output()
+        .expect("Failed to execute kelvin open");
+    assert!(output.status.success());
+    assert!(read_tasks_json(dir.path()).contains("New title"));
+}
+
+#[test]
+fn list_all_lists_merges_stores() {
+    let dir = tempfile::tempdir().unwrap();
+    kelvin_in(dir.path()).args(["add", "Default task"]).output().unwrap();
+    let kelvin_dir = dir.path().join(".config").join("kelvin");
+    std::fs::write(
+        kelvin_dir.join("work.json"),
+        r#"[{"id": 1, "title": "Work task", "description": "", "state": "melted",
+            "thaw_date": null, "due_date": null, "created_at": "2026-01-01"}]"#,
+    )
+    .unwrap();
+    std::fs::write(kelvin_dir.join("package.json"), r#"{"name": "not a list"}"#).unwrap();
+
+    let output = kelvin_in(dir.path())
+        .args(["list", "--all-lists"])
+        .output()
+        .expect("Failed to execute kelvin list --all-lists");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("List"));
+    assert!(stdout.lines().any(|l| l.starts_with("tasks") && l.contains("Default task")));
+    assert!(stdout.lines().any(|l| l.starts_with("work") && l.contains("Work task")));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("skipping"));
+}
+
+#[cfg(feature = "sqlite")]
+#[test]
+fn list_all_lists_reads_the_sqlite_list() {
+    let dir = tempfile::tempdir().unwrap();
+    let kelvin_dir = dir.path().join(".config").join("kelvin");
+    std::fs::create_dir_all(&kelvin_dir).unwrap();
+    std::fs::write(kelvin_dir.join("config.toml"), "[storage]\nbackend = \"sqlite\"\n").unwrap();
+    kelvin_in(dir.path()).args(["add", "Stored in sqlite"]).output().unwrap();
+    std::fs::write(
+        kelvin_dir.join("work.json"),
+        r#"[{"id": 1, "title": "Work task", "description": "", "state": "melted",
+            "thaw_date": null, "due_date": null, "created_at": "2026-01-01"}]"#,
+    )
+    .unwrap();
+
+    let output = kelvin_in(dir.path()).args(["list", "--all-lists"]).output().unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.lines().any(|l| l.starts_with("tasks") && l.contains("Stored in sqlite")));
+    assert!(stdout.lines().any(|l| l.starts_with("work") && l.contains("Work task")));
+}
+
+#[test]
+fn list_prefixes_address_named_lists() {
+    let dir = tempfile::tempdir().unwrap();
+    kelvin_in(dir.path()).args(["add", "Default task"]).output().unwrap();
+    let kelvin_dir = dir.path().join(".config").join("kelvin");
+    std::fs::write(kelvin_dir.join("config.toml"), "[lists.work]\nprefix = \"W\"\n").unwrap();
+    std::fs::write(
+        kelvin_dir.join("work.json"),
+        r#"[{"id": 3, "title": "Work task", "description": "", "state": "melted",
+            "thaw_date": null, "due_date": null, "created_at": "2026-01-01"}]"#,
+    )
+    .unwrap();
+
+    let output = kelvin_in(dir.path()).args(["list", "--all-lists"]).output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.lines().any(|l| l.contains("W-3") && l.contains("Work task")));
+    assert!(stdout.lines().any(|l| l.contains(" 1 ") && l.contains("Default task")));
+
+    let output = kelvin_in(dir.path()).args(["burn", "w-3"]).output().unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let work = std::fs::read_to_string(kelvin_dir.join("work.json")).unwrap();
+    assert!(work.contains("evaporated"));
+    assert!(!read_tasks_json(dir.path()).contains("evaporated"));
+
+    let output = kelvin_in(dir.path()).args(["show", "X-3"]).output().unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("No list has the ID prefix 'X'"));
+
+    let output = kelvin_in(dir.path()).args(["merge", "W-3", "1"]).output().unwrap();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("All task IDs must be in the same list"));
+}
+
+#[test]
+fn list_config_overrides_defaults_for_its_tasks() {
+    let dir = tempfile::tempdir().unwrap();
+    kelvin_in(dir.path()).args(["add", "Default task"]).output().unwrap();
+    let kelvin_dir = dir.path().join(".config").join("kelvin");
+    std::fs::write(
+        kelvin_dir.join("config.toml"),
+        "[defaults]\nthaw_days = 3\n[lists.work]\nprefix = \"W\"\nthaw_days = 14\n",
+    )
+    .unwrap();
+    std::fs::write(
+        kelvin_dir.join("work.json"),
+        r#"[{"id": 3, "title": "Work task", "description": "", "state": "melted",
+            "thaw_date": null, "due_date": null, "created_at": "2026-01-01"}]"#,
+    )
+    .unwrap();
+
+    let today = chrono::Local::now().date_naive();
+    let output = kelvin_in(dir.path()).args(["freeze", "W-3"]).output().unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let work = std::fs::read_to_string(kelvin_dir.join("work.json")).unwrap();
+    assert!(work.contains(&(today + chrono::Days::new(14)).to_string()));
+
+    let output = kelvin_in(dir.path()).args(["freeze", "1"]).output().unwrap();
+    assert!(output.status.success());
+    assert!(read_tasks_json(dir.path()).contains(&(today + chrono::Days::new(3)).to_string()));
+}
+
+#[test]
+fn in_flag_selects_a_list_and_its_overrides() {
+    let dir = tempfile::tempdir().unwrap();
+    let kelvin_dir = dir.path().join(".config").join("kelvin");
+    std::fs::create_dir_all(&kelvin_dir).unwrap();
+    std::fs::write(
+        kelvin_dir.join("config.toml"),
+        "[lists.work]\nprefix = \"W\"\ndefault_due = \"3d\"\nempty_message = \"Inbox zero\"\n",
+    )
+    .unwrap();
+    std::fs::write(kelvin_dir.join("work.json"), "[]").unwrap();
+
+    let output = kelvin_in(dir.path()).args(["list", "--in", "work"]).output().unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Inbox zero"));
+    let output = kelvin_in(dir.path()).arg("list").output().unwrap();
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("Inbox zero"));
+
+    let output = kelvin_in(dir.path()).args(["--in", "work", "add", "Review"]).output().unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let due = chrono::Local::now().date_naive() + chrono::Days::new(3);
+    let work = std::fs::read_to_string(kelvin_dir.join("work.json")).unwrap();
+    assert!(work.contains("Review") && work.contains(&due.to_string()));
+    kelvin_in(dir.path()).args(["add", "Groceries"]).output().unwrap();
+    let tasks: serde_json::Value = serde_json::from_str(&read_tasks_json(dir.path())).unwrap();
+    assert!(tasks[0]["due_date"].is_null());
+
+    let output = kelvin_in(dir.path()).args(["show", "W-1", "--in", "tasks"]).output().unwrap();
+    assert!(!output.status.success());
+    let output = kelvin_in(dir.path()).args(["list", "--in", "home"]).output().unwrap();
+    assert!(String::from_utf8_lossy(&output.stderr).contains("List 'home' not found"));
+}
+
+#[test]
+fn schema_prints_valid_json() {
+    let output = kelvin_cmd()
+        .arg("schema")
+        .output()
+        .expect("Failed to execute kelvin schema");
+    assert!(output.status.success());
+    let schema: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(schema["type"], "array");
+    assert!(schema["$defs"]["Task"]["properties"]["state"].is_object());
+}
+
+#[test]
+fn list_reverse_flips_order() {
+    let dir = tempfile::tempdir().unwrap();
+    for title in ["Alpha", "Bravo"] {
+        kelvin_in(dir.path()).args(["add", title]).output().unwrap();
+    }
+
+    let output = kelvin_in(dir.path())
+        .args(["list", "--reverse"])
+        .output()
+        .expect("Failed to execute kelvin list --reverse");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.find("Bravo").unwrap() < stdout.find("Alpha").unwrap());
+
+    // --limit applies after reversing
+    let output = kelvin_in(dir.path())
+        .args(["list", "--reverse", "--limit", "1"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Bravo"));
+    assert!(!stdout.contains("Alpha"));
+}
+
+#[test]
+fn empty_list_shows_configured_message() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = kelvin_in(dir.path()).args(["list"]).output().unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("All clear!"));
+
+    // Other views keep the plain message
+    let output = kelvin_in(dir.path()).args(["list", "--iced"]).output().unwrap();
+    assert!(String::from_utf8_lossy(&output.stdout).contains("No tasks found."));
+
+    // An empty message disables the celebration
+    let kelvin_dir = dir.path().join(".config").join("kelvin");
+    std::fs::create_dir_all(&kelvin_dir).unwrap();
+    std::fs::write(kelvin_dir.join("config.toml"), "[defaults]\nempty_message = \"\"\n").unwrap();
+    let output = kelvin_in(dir.path()).args(["list"]).output().unwrap();
+    assert!(String::from_utf8_lossy(&output.stdout).contains("No tasks found."));
+}
+
+#[test]
+fn undo_reverts_last_change_and_lists_snapshots() {
+    let dir = tempfile::tempdir().unwrap();
+    kelvin_in(dir.path()).args(["add", "Undo me"]).output().unwrap();
+    kelvin_in(dir.path()).args(["burn", "1"]).output().unwrap();
+
+    let output = kelvin_in(dir.path())
+        .args(["undo", "--list"])
+        .output()
+        .expect("Failed to execute kelvin undo --list");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let burn_line = stdout.lines().position(|l| l.contains("state change #1")).unwrap();
+    let add_line = stdout.lines().position(|l| l.contains("+1 task")).unwrap();
+    assert!(burn_line < add_line, "newest snapshot should be listed first");
+
+    let output = kelvin_in(dir.path())
+        .args(["undo"])
+        .output()
+        .expect("Failed to execute kelvin undo");
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Undid: state change #1"));
+
+    let output = kelvin_in(dir.path()).args(["list"]).output().unwrap();
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Undo me"));
+}
+
+#[test]
+fn edit_prints_changed_fields_only() {
+    let dir = tempfile::tempdir().unwrap();
+    kelvin_in(dir.path()).args(["add", "Old"]).output().unwrap();
+
+    let output = kelvin_in(dir.path())
+        .args(["edit", "1", "-t", "New", "--due", "2026-03-01"])
+        .output()
+        .expect("Failed to execute kelvin edit");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("title: 'Old' → 'New'"));
+    assert!(stdout.contains("due: - → 2026-03-01"));
+    assert!(!stdout.contains("description:"));
+    assert!(!stdout.contains("thaw:"));
+
+    // Re-applying the same values changes nothing
+    let output = kelvin_in(dir.path())
+        .args(["edit", "1", "-t", "New"])
+        .output()
+        .unwrap();
+    assert!(String::from_utf8_lossy(&output.stdout).contains("No changes"));
+}
+
+#[test]
+fn list_width_truncates_titles_to_fit() {
+    let dir = tempfile::tempdir().unwrap();
+    kelvin_in(dir.path())
+        .args(["add", "A very long task title that cannot possibly fit"])
+        .output()
+        .unwrap();
+
+    let output = kelvin_in(dir.path())
+        .args(["list", "--width", "60"])
+        .output()
+        .expect("Failed to execute kelvin list --width");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains('…'));
+    assert!(!stdout.contains("cannot possibly fit"));
+    for line in stdout.lines() {
+        assert!(line.chars().count() <= 60, "line too wide: {line}");
+    }
+}
+
+#[test]
+fn list_aligns_columns_after_wide_characters() {
+    use unicode_width::UnicodeWidthStr;
+
+    let dir = tempfile::tempdir().unwrap();
+    for title in ["日本語のタスク 🎉", "Plain task", "Cafe\u{301} visit"] {
+        kelvin_in(dir.path()).args(["add", title, "--due", "2026-03-01"]).output().unwrap();
+    }
+
+    let output = kelvin_in(dir.path()).args(["list"]).output().unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    // The due date column starts at the same terminal column on every row
+    let offsets: Vec<usize> = stdout
+        .lines()
+        .filter_map(|line| line.find("2026-03-01").map(|i| line[..i].width()))
+        .collect();
+    assert_eq!(offsets.len(), 3, "{stdout}");
+    assert!(offsets.iter().all(|&o| o == offsets[0]), "misaligned:\n{stdout}");
+}
+
+#[test]
+fn export_ics_emits_events_for_due_tasks() {
+    let dir = tempfile::tempdir().unwrap();
+    kelvin_in(dir.path())
+        .args(["add", "Release", "--due", "2026-03-01"])
+        .output()
+        .unwrap();
+    kelvin_in(dir.path()).args(["add", "Someday"]).output().unwrap();
+
+    let output = kelvin_in(dir.path())
+        .args(["export", "--format", "ics"])
+        .output()
+        .expect("Failed to execute kelvin export");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with("BEGIN:VCALENDAR\r\n"));
+    assert!(stdout.contains("SUMMARY:Release\r\n"));
+    assert!(stdout.contains("DTSTART;VALUE=DATE:20260301\r\n"));
+    assert!(!stdout.contains("Someday"));
+}
+
+#[test]
+fn list_id_range_filters_tasks() {
+    let dir = tempfile::tempdir().unwrap();
+    for title in ["First", "Second", "Third", "Fourth"] {
+        kelvin_in(dir.path()).args(["add", title]).output().unwrap();
+    }
+
+    let output = kelvin_in(dir.path())
+        .args(["list", "--id-range", "2-3"])
+        .output()
+        .expect("Failed to execute kelvin list --id-range");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!stdout.contains("First"));
+    assert!(stdout.contains("Second"));
+    assert!(stdout.contains("Third"));
+    assert!(!stdout.contains("Fourth"));
+
+    let output = kelvin_in(dir.path())
+        .args(["list", "--id-range", "3-2"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+}
+
+#[test]
+fn list_created_on_matches_exact_day() {
+    let dir = tempfile::tempdir().unwrap();
+    for title in ["Backdated", "Fresh"] {
+        kelvin_in(dir.path()).args(["add", title]).output().unwrap();
+    }
+    // Backdate the first task
+    let mut tasks: serde_json::Value = serde_json::from_str(&read_tasks_json(dir.path())).unwrap();
+    tasks[0]["created_at"] = "2026-01-15".into();
+    let tasks_path = dir.path().join(".config").join("kelvin").join("tasks.json");
+    std::fs::write(&tasks_path, tasks.to_string()).unwrap();
+
+    let output = kelvin_in(dir.path())
+        .args(["list", "--created-on", "2026-01-15"])
+        .output()
+        .expect("Failed to execute kelvin list --created-on");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Backdated"));
+    assert!(!stdout.contains("Fresh"));
+
+    let output = kelvin_in(dir.path())
+        .args(["list", "--created-on", "today"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!stdout.contains("Backdated"));
+    assert!(stdout.contains("Fresh"));
+}
+
+#[test]
+fn list_changed_since_uses_last_update() {
+    let dir = tempfile::tempdir().unwrap();
+    for title in ["Old", "Touched", "New"] {
+        kelvin_in(dir.path()).args(["add", title]).output().unwrap();
+    }
+    // Pretend the first two were last changed long ago
+    let mut tasks: serde_json::Value = serde_json::from_str(&read_tasks_json(dir.path())).unwrap();
+    assert!(tasks[2]["updated_at"].is_string());
+    for i in 0..2 {
+        tasks[i]["created_at"] = "2025-01-01".into();
+        tasks[i]["updated_at"] = "2025-01-01".into();
+    }
+    let tasks_path = dir.path().join(".config").join("kelvin").join("tasks.json");
+    std::fs::write(&tasks_path, tasks.to_string()).unwrap();
+    kelvin_in(dir.path()).args(["edit", "2", "--desc", "Worked on it"]).output().unwrap();
+
+    let output = kelvin_in(dir.path())
+        .args(["list", "--changed-since", "3d"])
+        .output()
+        .expect("Failed to execute kelvin list --changed-since");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!stdout.contains("Old"));
+    assert!(stdout.contains("Touched"));
+    assert!(stdout.contains("New"));
+
+    let output = kelvin_in(dir.path())
+        .args(["list", "--changed-since", "2024-12-31"])
+        .output()
+        .unwrap();
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Old"));
+}
+
+#[test]
+fn list_sort_accepts_several_keys() {
+    let dir = tempfile::tempdir().unwrap();
+    let adds: [&[&str]; 4] = [
+        &["add", "Low", "-p", "low", "--due", "2026-01-01"],
+        &["add", "High late", "-p", "high", "--due", "2026-03-01"],
+        &["add", "High undated", "-p", "high"],
+        &["add", "High early", "-p", "high", "--due", "2026-02-01"],
+    ];
+    for args in adds {
+        kelvin_in(dir.path()).args(args).output().unwrap();
+    }
+    let titles = |sort: &str| {
+        let output = kelvin_in(dir.path())
+            .args(["list", "--no-header", "--fields", "title", "--sort", sort])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(|l| l.trim().to_string())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(titles("priority,due"), ["High early", "High late", "High undated", "Low"]);
+    // Missing dates stay last when the direction flips
+    assert_eq!(titles("priority,due:desc"), ["High late", "High early", "High undated", "Low"]);
+}
+
+#[test]
+fn list_sort_breaks_ties_by_id() {
+    let dir = tempfile::tempdir().unwrap();
+    let kelvin_dir = dir.path().join(".config").join("kelvin");
+    std::fs::create_dir_all(&kelvin_dir).unwrap();
+    let task = |id: u32, due: &str| {
+        format!(
+            r#"{{"id": {id}, "title": "Task {id}", "description": "", "state": "melted",
+                "thaw_date": null, "due_date": "{due}", "created_at": "2026-01-01"}}"#
+        )
+    };
+    let tasks = [
+        task(5, "2030-02-01"),
+        task(2, "2030-02-01"),
+        task(7, "2030-01-01"),
+        task(3, "2030-02-01"),
+    ];
+    std::fs::write(kelvin_dir.join("tasks.json"), format!("[{}]", tasks.join(","))).unwrap();
+
+    let ids = |sort: &str| {
+        let output = kelvin_in(dir.path())
+            .args(["list", "--no-header", "--fields", "id", "--sort", sort])
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(|l| l.trim().to_string())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(ids("due"), ["7", "2", "3", "5"]);
+    assert_eq!(ids("due:desc"), ["2", "3", "5", "7"]);
+}
+
+#[test]
+fn list_sort_flag_overrides_configured_sort() {
+    let dir = tempfile::tempdir().unwrap();
+    kelvin_in(dir.path()).args(["add", "Later", "--due", "2030-05-01"]).output().unwrap();
+    kelvin_in(dir.path()).args(["add", "Undated"]).output().unwrap();
+    kelvin_in(dir.path()).args(["add", "Sooner", "--due", "2030-01-01"]).output().unwrap();
+    let kelvin_dir = dir.path().join(".config").join("kelvin");
+    std::fs::write(kelvin_dir.join("config.toml"), "[defaults]\nsort = \"due\"\n").unwrap();
+
+    let order = |args: &[&str]| {
+        let output = kelvin_in(dir.path()).args(args).output().unwrap();
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+        let mut titles = ["Later", "Undated", "Sooner"];
+        titles.sort_by_key(|t| stdout.find(t).unwrap());
+        titles
+    };
+    assert_eq!(order(&["list"]), ["Sooner", "Later", "Undated"]);
+    assert_eq!(order(&["list", "--reverse"]), ["Undated", "Later", "Sooner"]);
+    assert_eq!(order(&["list", "--sort", "id"]), ["Later", "Undated", "Sooner"]);
+
+    std::fs::write(kelvin_dir.join("config.toml"), "[defaults]\nsort = \"size\"\n").unwrap();
+    let output = kelvin_in(dir.path()).arg("list").output().unwrap();
+    assert!(!output.status.success());
+}
+
+#[test]
+fn burn_all_melted_confirms_and_skips_other_states() {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let dir = tempfile::tempdir().unwrap();
+    kelvin_in(dir.path()).args(["add", "Done one"]).output().unwrap();
+    kelvin_in(dir.path()).args(["add", "Done two"]).output().unwrap();
+    kelvin_in(dir.path()).args(["add", "Later", "-d", "2099-01-01"]).output().unwrap();
+
+    let run_with_answer = |answer: &str| {
+        let mut child = kelvin_in(dir.path())
+            .args(["burn", "--all-melted"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child.stdin.take().unwrap().write_all(answer.as_bytes()).unwrap();
+        child.wait_with_output().unwrap()
+    };
+
+    // Piped input is never taken as an answer, so even "y" leaves everything as it was
+    let output = run_with_answer("y\n");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Burn 2 Melted tasks?"));
+    assert!(stdout.contains("pass --yes"));
+    assert!(stdout.contains("Aborted."));
+    assert!(!read_tasks_json(dir.path()).contains("evaporated"));
+
+    let output = kelvin_in(dir.path()).args(["burn", "--all-melted", "--yes"]).output().unwrap();
+    assert!(output.status.success());
+    let tasks: serde_json::Value = serde_json::from_str(&read_tasks_json(dir.path())).unwrap();
+    let states: Vec<&str> = tasks
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|t| t["state"].as_str().unwrap())
+        .collect();
+    assert_eq!(states, ["evaporated", "evaporated", "iced"]);
+
+    // An ID and --all-melted cannot be combined
+    let output = kelvin_in(dir.path())
+        .args(["burn", "1", "--all-melted", "--yes"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+}
+
+#[test]
+fn json_output_with_plain_dates() {
+    let dir = tempfile::tempdir().unwrap();
+    kelvin_in(dir.path()).args(["add", "Dated", "--due", "2026-03-01"]).output().unwrap();
+
+    let output = kelvin_in(dir.path()).args(["list", "--json"]).output().unwrap();
+    assert!(output.status.success());
+    let tasks: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(tasks[0]["due_date"], "2026-03-01");
+
+    for args in [
+        &["list", "--json", "--plain-dates"][..],
+        &["export", "--plain-dates"],
+    ] {
+        let output = kelvin_in(dir.path()).args(args).output().unwrap();
+        assert!(output.status.success());
+        let tasks: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(tasks[0]["due_date"], 20513);
+        assert!(tasks[0]["thaw_date"].is_null());
+    }
+
+    let output = kelvin_in(dir.path())
+        .args(["show", "1", "--json", "--plain-dates"])
+        .output()
+        .unwrap();
+    let task: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(task["due_date"], 20513);
+    assert_eq!(task["title"], "Dated");
+
+    // Only meaningful for JSON output
+    let output = kelvin_in(dir.path()).args(["list", "--plain-dates"]).output().unwrap();
+    assert!(!output.status.success());
+    let output = kelvin_in(dir.path())
+        .args(["export", "--format", "ics", "--plain-dates"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+}
+
+#[test]
+fn list_json_compact_is_one_line() {
+    let dir = tempfile::tempdir().unwrap();
+    kelvin_in(dir.path()).args(["add", "First"]).output().unwrap();
+    kelvin_in(dir.path()).args(["add", "Second"]).output().unwrap();
+
+    let output = kelvin_in(dir.path()).args(["list", "--json", "--compact"]).output().unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout.lines().count(), 1);
+    assert!(!stdout.contains(": "));
+    let tasks: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(tasks[1]["title"], "Second");
+
+    let output = kelvin_in(dir.path()).args(["list", "--compact"]).output().unwrap();
+    assert!(!output.status.success());
+}
+
+#[test]
+fn list_json_pagination_wraps_tasks_with_metadata() {
+    let dir = tempfile::tempdir().unwrap();
+    for title in ["One", "Two", "Three"] {
+        kelvin_in(dir.path()).args(["add", title]).output().unwrap();
+    }
+
+    let output = kelvin_in(dir.path())
+        .args(["list", "--json", "--offset", "1", "--limit", "1"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let page: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(page["total"], 3);
+    assert_eq!(page["offset"], 1);
+    assert_eq!(page["limit"], 1);
+    assert_eq!(page["tasks"].as_array().unwrap().len(), 1);
+    assert_eq!(page["tasks"][0]["title"], "Two");
+
+    let output = kelvin_in(dir.path()).args(["list", "--json", "--offset", "2"]).output().unwrap();
+    let page: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(page["limit"].is_null());
+    assert_eq!(page["tasks"][0]["title"], "Three");
+
+    // Without pagination flags the bare array is kept
+    let output = kelvin_in(dir.path()).args(["list", "--json"]).output().unwrap();
+    let tasks: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(tasks.as_array().unwrap().len(), 3);
+}
+
+#[test]
+fn add_duplicate_title_note_and_unique() {
+    let dir = tempfile::tempdir().unwrap();
+    kelvin_in(dir.path()).args(["add", "Water plants"]).output().unwrap();
+
+    // Silent by default
+    let output = kelvin_in(dir.path()).args(["add", " water PLANTS"]).output().unwrap();
+    assert!(output.status.success());
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("Note:"));
+
+    let kelvin_dir = dir.path().join(".config").join("kelvin");
+    std::fs::write(kelvin_dir.join("config.toml"), "[defaults]\nwarn_duplicates = true\n").unwrap();
+    let output = kelvin_in(dir.path()).args(["add", "Water plants"]).output().unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Note: a task with a similar title already exists (#1)"));
+    assert!(stdout.contains("Added task 3"));
+
+    let output = kelvin_in(dir.path())
+        .args(["add", "Water plants", "--unique"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(!read_tasks_json(dir.path()).contains("\"id\": 4"));
+}
+
+#[test]
+fn list_fields_selects_and_orders_columns() {
+    let dir = tempfile::tempdir().unwrap();
+    kelvin_in(dir.path()).args(["add", "Pick columns", "--due", "2030-01-01"]).output().unwrap();
+
+    let output = kelvin_in(dir.path())
+        .args(["list", "--fields", "due,id"])
+        .output()
+        .expect("Failed to execute kelvin list --fields");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut lines = stdout.lines();
+    assert_eq!(lines.next().unwrap(), "Due Date      ID");
+    lines.next(); // rule
+    assert_eq!(lines.next().unwrap(), "2030-01-01    1");
+    assert!(!stdout.contains("Pick columns"));
+
+    let output = kelvin_in(dir.path())
+        .args(["list", "--fields", "id,priority"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("possible values: id, title, state, thaw, due, created"));
+}
+
+#[test]
+fn burn_reports_and_cascades_to_unblocked_tasks() {
+    let dir = tempfile::tempdir().unwrap();
+    kelvin_in(dir.path()).args(["add", "Foundation"]).output().unwrap();
+    kelvin_in(dir.path()).args(["add", "Walls", "--depends", "1", "-d", "2099-01-01"]).output().unwrap();
+    kelvin_in(dir.path()).args(["add", "Other"]).output().unwrap();
+    kelvin_in(dir.path()).args(["add", "Roof", "--depends", "1", "--depends", "3"]).output().unwrap();
+
+    let output = kelvin_in(dir.path())
+        .args(["burn", "1", "--cascade"])
+        .output()
+        .expect("Failed to execute kelvin burn --cascade");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    // Roof still waits on task 3
+    assert!(stdout.contains("This unblocks #2\n"));
+    assert!(stdout.contains("Thawed task 2 [Melting]: Walls"));
+
+    let tasks: serde_json::Value = serde_json::from_str(&read_tasks_json(dir.path())).unwrap();
+    let today = chrono::Local::now().date_naive().to_string();
+    assert_eq!(tasks[1]["state"], "melting");
+    assert_eq!(tasks[1]["thawed_at"], today.as_str());
+    assert_eq!(tasks[1]["thaw_date"], today.as_str());
+    assert_eq!(tasks[3]["state"], "melted");
+
+    // The thaw goes where automatic thaws go
+    kelvin_in(dir.path())
+        .args(["add", "Paint", "--depends", "3", "-d", "2099-01-01"])
+        .output()
+        .unwrap();
+    let output = kelvin_in(dir.path())
+        .args(["--thaw-to", "melted", "burn", "3", "--cascade"])
+        .output()
+        .unwrap();
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Thawed task 5 [Melted]: Paint"));
+}
+
+#[test]
+fn show_adds_relative_dates() {
+    let dir = tempfile::tempdir().unwrap();
+    kelvin_in(dir.path()).args(["add", "Soon", "-d", "3d", "--due", "5d"]).output().unwrap();
+    kelvin_in(dir.path()).args(["add", "Late"]).output().unwrap();
+
+    let output = kelvin_in(dir.path()).args(["show", "1"]).output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("(in 3 days)"));
+    assert!(stdout.contains("(in 5 days)"));
+
+    // Backdate the second task's due date
+    let mut tasks: serde_json::Value = serde_json::from_str(&read_tasks_json(dir.path())).unwrap();
+    tasks[1]["due_date"] = "2000-01-01".into();
+    let tasks_path = dir.path().join(".config").join("kelvin").join("tasks.json");
+    std::fs::write(&tasks_path, tasks.to_string()).unwrap();
+
+    let output = kelvin_in(dir.path()).args(["show", "2"]).output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("2000-01-01 (overdue "));
+    let thaw_line = stdout.lines().find(|l| l.starts_with("Thaw Date:")).unwrap();
+    assert!(thaw_line.trim_end().ends_with('-'));
+
+    kelvin_in(dir.path()).args(["burn", "2"]).output().unwrap();
+    let output = kelvin_in(dir.path()).args(["show", "2"]).output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("days ago)"));
+}
+
+#[test]
+fn config_check_reports_problems_without_touching_tasks() {
+    let dir = tempfile::tempdir().unwrap();
+    let kelvin_dir = dir.path().join(".config").join("kelvin");
+
+    let output = kelvin_in(dir.path()).args(["config", "check"]).output().unwrap();
+    assert!(output.status.success());
+
+    std::fs::create_dir_all(&kelvin_dir).unwrap();
+    std::fs::write(kelvin_dir.join("config.toml"), "[defaults]\nthaw_days = 14\n").unwrap();
+    let output = kelvin_in(dir.path()).args(["config", "check"]).output().unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("is valid"));
+
+    std::fs::write(kelvin_dir.join("config.toml"), "[defaults]\nthaw_dayz = 14\n").unwrap();
+    let output = kelvin_in(dir.path()).args(["config", "check"]).output().unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("defaults.thaw_dayz"));
+
+    std::fs::write(kelvin_dir.join("config.toml"), "[defaults]\nthaw_days = 0\n").unwrap();
+    let output = kelvin_in(dir.path()).args(["config", "check"]).output().unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("thaw_days"));
+
+    assert!(!kelvin_dir.join("tasks.json").exists());
+}
+
+#[test]
+fn priority_is_stored_shown_and_edited() {
+    let dir = tempfile::tempdir().unwrap();
+    kelvin_in(dir.path()).args(["add", "Urgent", "-p", "high"]).output().unwrap();
+    kelvin_in(dir.path()).args(["add", "Whenever"]).output().unwrap();
+
+    let tasks: serde_json::Value = serde_json::from_str(&read_tasks_json(dir.path())).unwrap();
+    assert_eq!(tasks[0]["priority"], "high");
+    // Normal is the default and is not written out
+    assert!(tasks[1].get("priority").is_none());
+
+    let output = kelvin_in(dir.path()).args(["show", "1"]).output().unwrap();
+    assert!(String::from_utf8_lossy(&output.stdout).contains("High"));
+
+    let output = kelvin_in(dir.path())
+        .args(["edit", "1", "--priority", "low"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("priority: High → Low"));
+
+    let output = kelvin_in(dir.path()).args(["add", "Bad", "-p", "urgent"]).output().unwrap();
+    assert!(!output.status.success());
+}
+
+#[test]
+fn list_group_by_tag() {
+    let dir = tempfile::tempdir().unwrap();
+    kelvin_in(dir.path()).args(["add", "Both", "--tag", "Work", "--tag", "home"]).output().unwrap();
+    kelvin_in(dir.path()).args(["add", "Office", "--tag", "work"]).output().unwrap();
+    kelvin_in(dir.path()).args(["add", "Loose"]).output().unwrap();
+
+    let tasks: serde_json::Value = serde_json::from_str(&read_tasks_json(dir.path())).unwrap();
+    assert_eq!(tasks[0]["tags"], serde_json::json!(["work", "home"]));
+
+    let output = kelvin_in(dir.path())
+        .args(["list", "--group-by", "tag"])
+        .output()
+        .expect("Failed to execute kelvin list --group-by tag");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let home = stdout.find("home (1)").unwrap();
+    let work = stdout.find("work (2)").unwrap();
+    let none = stdout.find("(none) (1)").unwrap();
+    assert!(home < work && work < none);
+    assert_eq!(stdout.matches("Both").count(), 2);
+
+    let output = kelvin_in(dir.path())
+        .args(["list", "--group-by", "tag", "--group-sort", "count"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.find("work (2)").unwrap() < stdout.find("home (1)").unwrap());
+}
+
+#[test]
+fn list_temp_shows_due_proximity() {
+    let dir = tempfile::tempdir().unwrap();
+    kelvin_in(dir.path()).args(["add", "Close", "--due", "1d"]).output().unwrap();
+    kelvin_in(dir.path()).args(["add", "Far", "--due", "8w"]).output().unwrap();
+    kelvin_in(dir.path()).args(["add", "Open-ended"]).output().unwrap();
+
+    let output = kelvin_in(dir.path()).args(["list", "--temp"]).output().unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let glyph_of = |title: &str| {
+        let line = stdout.lines().find(|l| l.contains(title)).unwrap();
+        line.chars().next().unwrap()
+    };
+    assert_eq!(glyph_of("Close"), '▅');
+    assert_eq!(glyph_of("Far"), '▁');
+    assert_eq!(glyph_of("Open-ended"), '·');
+}
+
+#[test]
+fn freeze_by_state_freezes_only_matching_tasks() {
+    let dir = tempfile::tempdir().unwrap();
+    kelvin_in(dir.path()).args(["add", "Leftover one"]).output().unwrap();
+    kelvin_in(dir.path()).args(["add", "Leftover two"]).output().unwrap();
+    kelvin_in(dir.path()).args(["add", "Done"]).output().unwrap();
+    kelvin_in(dir.path()).args(["burn", "3"]).output().unwrap();
+
+    let output = kelvin_in(dir.path())
+        .args(["freeze", "--state", "melted", "-d", "2099-01-01", "--yes"])
+        .output()
+        .expect("Failed to execute kelvin freeze --state");
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Froze 2 tasks."));
+
+    let tasks: serde_json::Value = serde_json::from_str(&read_tasks_json(dir.path())).unwrap();
+    assert_eq!(tasks[0]["state"], "iced");
+    assert_eq!(tasks[1]["thaw_date"], "2099-01-01");
+    assert_eq!(tasks[2]["state"], "evaporated");
+
+    let output = kelvin_in(dir.path())
+        .args(["freeze", "1", "--state", "melted"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+}
+
+#[test]
+fn stats_counts_states_and_completions_per_week() {
+    let dir = tempfile::tempdir().unwrap();
+    for title in ["One", "Two", "Three"] {
+        kelvin_in(dir.path()).args(["add", title]).output().unwrap();
+    }
+    kelvin_in(dir.path()).args(["burn", "1"]).output().unwrap();
+    kelvin_in(dir.path()).args(["burn", "2"]).output().unwrap();
+
+    let tasks: serde_json::Value = serde_json::from_str(&read_tasks_json(dir.path())).unwrap();
+    assert!(tasks[0]["completed_at"].is_string());
+    assert!(tasks[2].get("completed_at").is_none());
+
+    let output = kelvin_in(dir.path()).arg("stats").output().unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Evaporated   2"));
+    assert!(stdout.contains("Total        3"));
+
+    let output = kelvin_in(dir.path()).args(["stats", "--by-week", "4"]).output().unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), 4);
+    // Only the current week has completions
+    assert!(lines[3].contains("  2  █"));
+    assert!(lines[0].contains("  0"));
+    assert!(!lines[0].contains('█'));
+
+    // A refrozen task is no longer done
+    kelvin_in(dir.path()).args(["freeze", "1", "-d", "2099-01-01"]).output().unwrap();
+    let tasks: serde_json::Value = serde_json::from_str(&read_tasks_json(dir.path())).unwrap();
+    assert!(tasks[0].get("completed_at").is_none());
+    let output = kelvin_in(dir.path()).args(["stats", "--by-week", "1"]).output().unwrap();
+    assert!(String::from_utf8_lossy(&output.stdout).contains("  1  █"));
+}
+
+#[test]
+fn list_no_header_prints_only_rows() {
+    let dir = tempfile::tempdir().unwrap();
+    kelvin_in(dir.path()).args(["add", "Row one"]).output().unwrap();
+    kelvin_in(dir.path()).args(["add", "Row two"]).output().unwrap();
+
+    let output = kelvin_in(dir.path()).args(["list", "--no-header"]).output().unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].starts_with("1 ") && lines[0].contains("Row one"));
+    assert!(!stdout.contains('─'));
+}
+
+#[test]
+fn task_url_is_validated_shown_and_cleared() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = kelvin_in(dir.path())
+        .args(["add", "Review", "--url", "https://example.com/pr/1"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    // Piped output is plain text, without the OSC 8 escape
+    let output = kelvin_in(dir.path()).args(["show", "1"]).output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("https://example.com/pr/1\n"));
+    assert!(!stdout.contains("\x1b]8"));
+
+    let output = kelvin_in(dir.path())
+        .args(["add", "Broken", "--url", "example.com"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+
+    kelvin_in(dir.path()).args(["edit", "1", "--url", ""]).output().unwrap();
+    assert!(!read_tasks_json(dir.path()).contains("url"));
+    let output = kelvin_in(dir.path()).args(["open-url", "1"]).output().unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("has no URL"));
+}
+
+#[test]
+fn auto_purge_removes_long_evaporated_tasks() {
+    let dir = tempfile::tempdir().unwrap();
+    kelvin_in(dir.path()).args(["add", "Ancient"]).output().unwrap();
+    kelvin_in(dir.path()).args(["add", "Just done"]).output().unwrap();
+    kelvin_in(dir.path()).args(["burn", "1"]).output().unwrap();
+    kelvin_in(dir.path()).args(["burn", "2"]).output().unwrap();
+
+    // Backdate the first completion
+    let mut tasks: serde_json::Value = serde_json::from_str(&read_tasks_json(dir.path())).unwrap();
+    tasks[0]["completed_at"] = "2000-01-01".into();
+    let kelvin_dir = dir.path().join(".config").join("kelvin");
+    std::fs::write(kelvin_dir.join("tasks.json"), tasks.to_string()).unwrap();
+
+    // Disabled by default
+    kelvin_in(dir.path()).args(["list", "--all"]).output().unwrap();
+    assert!(read_tasks_json(dir.path()).contains("Ancient"));
+
+    std::fs::write(kelvin_dir.join("config.toml"), "[defaults]\nauto_purge_days = 30\n").unwrap();
+    let output = kelvin_in(dir.path()).args(["list", "--all"]).output().unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Purged 1 task"));
+    let stored = read_tasks_json(dir.path());
+    assert!(!stored.contains("Ancient"));
+    assert!(stored.contains("Just done"));
+}
+
+#[test]
+fn show_history_lists_transitions() {
+    let dir = tempfile::tempdir().unwrap();
+    kelvin_in(dir.path()).args(["add", "Storied"]).output().unwrap();
+    kelvin_in(dir.path()).args(["add", "Bystander"]).output().unwrap();
+    kelvin_in(dir.path()).args(["freeze", "1", "-d", "3d"]).output().unwrap();
+    kelvin_in(dir.path()).args(["warm", "1"]).output().unwrap();
+    kelvin_in(dir.path()).args(["burn", "1"]).output().unwrap();
+
+    let output = kelvin_in(dir.path()).args(["show", "1", "--history"]).output().unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let history = &stdout[stdout.find("History:").unwrap()..];
+    let events: Vec<&str> = history
+        .lines()
+        .skip(1)
+        .map(|l| l.split_whitespace().nth(1).unwrap())
+        .collect();
+    assert_eq!(events, ["added", "frozen", "warmed", "burned"]);
+}
+
+#[test]
+fn triage_refuses_piped_stdin() {
+    let dir = tempfile::tempdir().unwrap();
+    kelvin_in(dir.path()).args(["add", "Inbox item"]).output().unwrap();
+
+    let output = kelvin_in(dir.path())
+        .arg("triage")
+        .stdin(std::process::Stdio::piped())
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("needs a terminal"));
+    assert!(read_tasks_json(dir.path()).contains("\"melted\""));
+}
+
+#[test]
+fn add_inherit_due_copies_dependency_deadline() {
+    let dir = tempfile::tempdir().unwrap();
+    kelvin_in(dir.path()).args(["add", "Release", "--due", "2030-05-01"]).output().unwrap();
+    kelvin_in(dir.path()).args(["add", "Undated"]).output().unwrap();
+
+    let output = kelvin_in(dir.path())
+        .args(["add", "Changelog", "--depends", "1", "--depends", "2", "--inherit-due"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let output = kelvin_in(dir.path())
+        .args(["add", "Slides", "--depends", "1", "--inherit-due", "--due", "2030-04-01"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let tasks: serde_json::Value = serde_json::from_str(&read_tasks_json(dir.path())).unwrap();
+    assert_eq!(tasks[2]["due_date"], "2030-05-01");
+    assert_eq!(tasks[3]["due_date"], "2030-04-01");
+
+    let output = kelvin_in(dir.path()).args(["add", "Orphan", "--inherit-due"]).output().unwrap();
+    assert!(!output.status.success());
+    let output = kelvin_in(dir.path())
+        .args(["add", "Ghost", "--depends", "9", "--inherit-due"])
+        .output()
+        .unwrap();
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Task 9 not found"));
+}
+
+#[test]
+fn add_after_sequences_tasks() {
+    let dir = tempfile::tempdir().unwrap();
+    kelvin_in(dir.path()).args(["add", "Build", "--due", "2030-01-10"]).output().unwrap();
+    let output = kelvin_in(dir.path())
+        .args(["add", "Deploy", "--after", "1", "--inherit-due", "--tag", "ops"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let tasks: serde_json::Value = serde_json::from_str(&read_tasks_json(dir.path())).unwrap();
+    assert_eq!(tasks[1]["depends_on"], serde_json::json!([1]));
+    assert_eq!(tasks[1]["due_date"], "2030-01-10");
+
+    let output = kelvin_in(dir.path()).args(["add", "Nope", "--after", "7"]).output().unwrap();
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Task 7 not found"));
+    let output = kelvin_in(dir.path()).args(["add", "Self", "--after", "3"]).output().unwrap();
+    assert!(String::from_utf8_lossy(&output.stderr).contains("cannot depend on itself"));
+}
+
+#[test]
+fn reopen_restores_state_before_burn() {
+    let dir = tempfile::tempdir().unwrap();
+    kelvin_in(dir.path()).args(["add", "Someday", "-d", "2030-01-01"]).output().unwrap();
+    kelvin_in(dir.path()).args(["burn", "1"]).output().unwrap();
+
+    let output = kelvin_in(dir.path()).args(["reopen", "1"]).output().unwrap();
+    assert!(output.status.success());
+    let tasks: serde_json::Value = serde_json::from_str(&read_tasks_json(dir.path())).unwrap();
+    assert_eq!(tasks[0]["state"], "iced");
+    assert_eq!(tasks[0]["thaw_date"], "2030-01-01");
+
+    // Only completed tasks can be reopened
+    let output = kelvin_in(dir.path()).args(["reopen", "1"]).output().unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Only Evaporated tasks"));
+}
+
+#[test]
+fn confirm_destructive_guards_undo() {
+    let dir = tempfile::tempdir().unwrap();
+    let config_dir = dir.path().join(".config").join("kelvin");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    std::fs::write(config_dir.join("config.toml"), "[defaults]\nconfirm_destructive = true\n").unwrap();
+    kelvin_in(dir.path()).args(["add", "Keep me"]).output().unwrap();
+
+    let output = kelvin_in(dir.path()).arg("undo").output().unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Aborted."));
+    assert!(read_tasks_json(dir.path()).contains("Keep me"));
+
+    let output = kelvin_in(dir.path()).args(["--yes", "undo"]).output().unwrap();
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Undid:"));
+    assert!(!read_tasks_json(dir.path()).contains("Keep me"));
+}
+
+#[test]
+fn list_no_due_and_has_due() {
+    let dir = tempfile::tempdir().unwrap();
+    kelvin_in(dir.path()).args(["add", "Deadline", "--due", "2030-01-01"]).output().unwrap();
+    kelvin_in(dir.path()).args(["add", "Open-ended"]).output().unwrap();
+    kelvin_in(dir.path()).args(["add", "Parked", "-d", "2030-01-01"]).output().unwrap();
+
+    let titles = |args: &[&str]| {
+        let output = kelvin_in(dir.path()).args(args).output().unwrap();
+        assert!(output.status.success());
+        let tasks: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        tasks
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t["title"].as_str().unwrap().to_string())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(titles(&["list", "--no-due", "--json"]), ["Open-ended"]);
+    assert_eq!(titles(&["list", "--all", "--no-due", "--json"]), ["Open-ended", "Parked"]);
+    assert_eq!(titles(&["list", "--has-due", "--json"]), ["Deadline"]);
+
+    let output = kelvin_in(dir.path()).args(["list", "--no-due", "--has-due"]).output().unwrap();
+    assert!(!output.status.success());
+}
+
+#[test]
+fn large_store_warning_is_printed_once() {
+    let dir = tempfile::tempdir().unwrap();
+    let config_dir = dir.path().join(".config").join("kelvin");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    std::fs::write(config_dir.join("config.toml"), "[defaults]\nlarge_store_warn = 2\n").unwrap();
+    for title in ["One", "Two"] {
+        let output = kelvin_in(dir.path()).args(["add", title]).output().unwrap();
+        assert!(!String::from_utf8_lossy(&output.stderr).contains("large_store_warn"));
+    }
+
+    let output = kelvin_in(dir.path()).args(["add", "Three"]).output().unwrap();
+    assert!(output.status.success());
+    let output = kelvin_in(dir.path()).args(["burn", "1"]).output().unwrap();
+    assert!(output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(stderr.matches("large_store_warn").count(), 1);
+
+    std::fs::write(config_dir.join("config.toml"), "[defaults]\nlarge_store_warn = 0\n").unwrap();
+    let output = kelvin_in(dir.path()).arg("list").output().unwrap();
+    assert!(output.stderr.is_empty());
+}
+
+#[test]
+fn list_group_by_state_sorts_within_groups() {
+    let dir = tempfile::tempdir().unwrap();
+    for (title, due, thaw) in [
+        ("Melted late", "2030-03-01", None),
+        ("Iced late", "2030-02-01", Some("2099-01-01")),
+        ("Melted early", "2030-01-01", None),
+        ("Iced early", "2030-01-15", Some("2099-01-01")),
+        ("Melted undated", "", None),
+    ] {
+        let mut args = vec!["add", title];
+        if !due.is_empty() {
+            args.extend(["--due", due]);
+        }
+        if let Some(thaw) = thaw {
+            args.extend(["-d", thaw]);
+        }
+        kelvin_in(dir.path()).args(&args).output().unwrap();
+    }
+
+    let output = kelvin_in(dir.path())
+        .args(["list", "--all", "--group-by", "state", "--sort", "due", "--fields", "title"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = stdout
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('─') && *l != "Task")
+        .collect();
+    assert_eq!(
+        lines,
+        [
+            "Iced (2)",
+            "Iced early",
+            "Iced late",
+            "Melted (3)",
+            "Melted early",
+            "Melted late",
+            "Melted undated",
+        ]
+    );
+}
+
+#[test]
+fn clone_copies_a_task_as_melted() {
+    let dir = tempfile::tempdir().unwrap();
+    kelvin_in(dir.path())
+        .args(["add", "Weekly report", "--desc", "Numbers", "--due", "2030-01-01", "-p", "high"])
+        .args(["--tag", "work", "-d", "2099-01-01"])
+        .output()
+        .unwrap();
+    kelvin_in(dir.path()).args(["burn", "1"]).output().unwrap();
+
+    let output = kelvin_in(dir.path()).args(["clone", "1"]).output().unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Cloned task 1 as 2"));
+    let output = kelvin_in(dir.path()).args(["clone", "1", "--title", "Monthly report"]).output().unwrap();
+    assert!(output.status.success());
+
+    let tasks: serde_json::Value = serde_json::from_str(&read_tasks_json(dir.path())).unwrap();
+    let copy = &tasks[1];
+    assert_eq!(copy["title"], "Weekly report");
+    assert_eq!(copy["description"], "Numbers");
+    assert_eq!(copy["state"], "melted");
+    assert_eq!(copy["due_date"], "2030-01-01");
+    assert_eq!(copy["priority"], "high");
+    assert_eq!(copy["tags"], serde_json::json!(["work"]));
+    assert!(copy["thaw_date"].is_null());
+    assert!(copy.get("completed_at").is_none());
+    assert_eq!(tasks[2]["title"], "Monthly report");
+
+    let output = kelvin_in(dir.path()).args(["clone", "9"]).output().unwrap();
+    assert!(!output.status.success());
+}
+
+#[test]
+fn list_melting_and_melted_shortcuts() {
+    let dir = tempfile::tempdir().unwrap();
+    kelvin_in(dir.path()).args(["add", "Ready"]).output().unwrap();
+    kelvin_in(dir.path()).args(["add", "Thawing", "-d", "2099-01-01"]).output().unwrap();
+    // Thaw date already passed: auto-thaws to Melting on the next load
+    let path = dir.path().join(".config").join("kelvin").join("tasks.json");
+    let mut tasks: serde_json::Value = serde_json::from_str(&read_tasks_json(dir.path())).unwrap();
+    tasks[1]["thaw_date"] = "2000-01-01".into();
+    std::fs::write(&path, tasks.to_string()).unwrap();
+
+    let stdout = |args: &[&str]| {
+        let output = kelvin_in(dir.path()).args(args).output().unwrap();
+        assert!(output.status.success());
+        String::from_utf8_lossy(&output.stdout).to_string()
+    };
+    let melting = stdout(&["list", "--melting"]);
+    assert!(melting.contains("Thawing") && !melting.contains("Ready"));
+    let melted = stdout(&["list", "--melted"]);
+    assert!(melted.contains("Ready") && !melted.contains("Thawing"));
+
+    let output = kelvin_in(dir.path()).args(["list", "--melting", "--all"]).output().unwrap();
+    assert!(!output.status.success());
+}
+
+#[test]
+fn list_dims_stale_titles_when_colored() {
+    let dir = tempfile::tempdir().unwrap();
+    let config_dir = dir.path().join(".config").join("kelvin");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    std::fs::write(config_dir.join("config.toml"), "[defaults]\nstale_days = 30\n").unwrap();
+    kelvin_in(dir.path()).args(["add", "Ancient"]).output().unwrap();
+    kelvin_in(dir.path()).args(["add", "Fresh"]).output().unwrap();
+    kelvin_in(dir.path()).args(["add", "Revisited"]).output().unwrap();
+    let path = config_dir.join("tasks.json");
+    let mut tasks: serde_json::Value = serde_json::from_str(&read_tasks_json(dir.path())).unwrap();
+    tasks[0]["created_at"] = "2000-01-01".into();
+    tasks[0]["updated_at"] = "2000-01-02".into();
+    // Old, but changed recently
+    tasks[2]["created_at"] = "2000-01-01".into();
+    std::fs::write(&path, tasks.to_string()).unwrap();
+    kelvin_in(dir.path()).args(["edit", "3", "-t", "Revisited today"]).output().unwrap();
+
+    let output = kelvin_in(dir.path())
+        .args(["list", "--fields", "title"])
+        .env("CLICOLOR_FORCE", "1")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("\x1b[2mAncient"));
+    assert!(!stdout.contains("\x1b[2mFresh"));
+    assert!(!stdout.contains("\x1b[2mRevisited"));
+    assert!(stdout.contains("Revisited today"));
+}
+
+#[test]
+fn set_id_renumbers_and_fixes_dependencies() {
+    let dir = tempfile::tempdir().unwrap();
+    kelvin_in(dir.path()).args(["add", "Base"]).output().unwrap();
+    kelvin_in(dir.path()).args(["add", "On top", "--after", "1"]).output().unwrap();
+    kelvin_in(dir.path()).args(["freeze", "1", "-d", "3d"]).output().unwrap();
+
+    let output = kelvin_in(dir.path()).args(["set-id", "1", "42"]).output().unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("(1 dependency updated)"));
+    let tasks: serde_json::Value = serde_json::from_str(&read_tasks_json(dir.path())).unwrap();
+    assert_eq!(tasks[0]["id"], 42);
+    assert_eq!(tasks[1]["depends_on"], serde_json::json!([42]));
+
+    // History follows the task to its new ID
+    let output = kelvin_in(dir.path()).args(["show", "42", "--history"]).output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("added"));
+    assert!(stdout.contains("frozen"));
+    assert!(stdout.contains("renumbered from #1"));
+    assert!(!stdout.contains("deleted"));
+
+    for (args, message) in [
+        (["mv", "42", "2"], "already taken"),
+        (["mv", "42", "0"], "start at 1"),
+        (["mv", "7", "8"], "Task 7 not found"),
+    ] {
+        let output = kelvin_in(dir.path()).args(args).output().unwrap();
+        assert!(!output.status.success());
+        assert!(String::from_utf8_lossy(&output.stderr).contains(message));
+    }
+}
+
+#[test]
+fn report_sums_estimates_in_range() {
+    let dir = tempfile::tempdir().unwrap();
+    kelvin_in(dir.path()).args(["add", "Design", "--estimate", "1h30m"]).output().unwrap();
+    kelvin_in(dir.path()).args(["add", "Build", "--estimate", "2h"]).output().unwrap();
+    kelvin_in(dir.path()).args(["add", "Calls"]).output().unwrap();
+    kelvin_in(dir.path()).args(["add", "Still open", "--estimate", "5h"]).output().unwrap();
+    for id in ["1", "2", "3"] {
+        kelvin_in(dir.path()).args(["burn", id]).output().unwrap();
+    }
+    let path = dir.path().join(".config").join("kelvin").join("tasks.json");
+    let mut tasks: serde_json::Value = serde_json::from_str(&read_tasks_json(dir.path())).unwrap();
+    tasks[0]["completed_at"] = "2026-03-02".into();
+    tasks[1]["completed_at"] = "2026-03-31".into();
+    tasks[2]["completed_at"] = "2026-03-15".into();
+    std::fs::write(&path, tasks.to_string()).unwrap();
+
+    let output = kelvin_in(dir.path())
+        .args(["report", "--from", "2026-03-01", "--to", "2026-03-20"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Design") && stdout.contains("Calls"));
+    assert!(!stdout.contains("Build") && !stdout.contains("Still open"));
+    assert!(stdout.contains("Total: 1h30m (2 tasks, 1 without estimate)"));
+
+    let output = kelvin_in(dir.path())
+        .args(["report", "--from", "2026-03-01", "--to", "2026-03-31"])
+        .output()
+        .unwrap();
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Total: 3h30m (3 tasks"));
+
+    let output = kelvin_in(dir.path())
+        .args(["report", "--from", "2026-04-01", "--to", "2026-03-01"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let output = kelvin_in(dir.path()).args(["add", "Bad", "--estimate", "soon"]).output().unwrap();
+    assert!(!output.status.success());
+}
+
+#[test]
+fn list_min_priority() {
+    let dir = tempfile::tempdir().unwrap();
+    kelvin_in(dir.path()).args(["add", "Someday", "-p", "low"]).output().unwrap();
+    kelvin_in(dir.path()).args(["add", "Regular"]).output().unwrap();
+    kelvin_in(dir.path()).args(["add", "Urgent", "-p", "high"]).output().unwrap();
+
+    let titles = |min: &str| {
+        let output = kelvin_in(dir.path())
+            .args(["list", "--min-priority", min, "--fields", "title", "--no-header"])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(|l| l.trim().to_string())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(titles("high"), ["Urgent"]);
+    assert_eq!(titles("normal"), ["Regular", "Urgent"]);
+    assert_eq!(titles("low").len(), 3);
+}
+
+#[test]
+fn list_iced_sorts_by_thaw_with_countdown() {
+    let dir = tempfile::tempdir().unwrap();
+    kelvin_in(dir.path()).args(["add", "Later", "-d", "10d"]).output().unwrap();
+    kelvin_in(dir.path()).args(["add", "Sooner", "-d", "3d"]).output().unwrap();
+
+    let output = kelvin_in(dir.path()).args(["list", "--iced", "--no-header"]).output().unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert!(lines[0].contains("in 3d") && lines[0].contains("Sooner"));
+    assert!(lines[1].contains("in 10d") && lines[1].contains("Later"));
+
+    // An explicit --sort still wins
+    let output = kelvin_in(dir.path())
+        .args(["list", "--iced", "--no-header", "--sort", "id"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.lines().next().unwrap().contains("Later"));
+}
+
+#[test]
+fn merge_combines_tasks_and_redirects_dependencies() {
+    let dir = tempfile::tempdir().unwrap();
+    kelvin_in(dir.path())
+        .args(["add", "Write docs", "--desc", "API", "--tag", "docs", "--due", "2030-02-01"])
+        .output()
+        .unwrap();
+    kelvin_in(dir.path())
+        .args(["add", "Document API", "--desc", "Examples", "--tag", "api", "--due", "2030-01-15"])
+        .args(["-p", "high", "--estimate", "1h"])
+        .output()
+        .unwrap();
+    kelvin_in(dir.path()).args(["add", "Publish", "--after", "2"]).output().unwrap();
+
+    let output = kelvin_in(dir.path()).args(["merge", "2", "1"]).output().unwrap();
+    assert!(output.status.success());
+
+    let tasks: serde_json::Value = serde_json::from_str(&read_tasks_json(dir.path())).unwrap();
+    let tasks = tasks.as_array().unwrap();
+    assert_eq!(tasks.len(), 2);
+    let merged = &tasks[0];
+    assert_eq!(merged["title"], "Write docs");
+    assert_eq!(merged["description"], "API\n\nExamples");
+    assert_eq!(merged["tags"], serde_json::json!(["docs", "api"]));
+    assert_eq!(merged["due_date"], "2030-01-15");
+    assert_eq!(merged["priority"], "high");
+    assert_eq!(merged["estimate"], 60);
+    assert_eq!(tasks[1]["depends_on"], serde_json::json!([1]));
+
+    for args in [["merge", "1", "1"], ["merge", "9", "1"]] {
+        let output = kelvin_in(dir.path()).args(args).output().unwrap();
+        assert!(!output.status.success());
+    }
+}
+
+#[test]
+fn burn_refuses_open_subtasks_without_ignore_subtasks() {
+    let dir = tempfile::tempdir().unwrap();
+    kelvin_in(dir.path()).args(["add", "Release"]).output().unwrap();
+    kelvin_in(dir.path())
+        .args(["edit", "1", "--subtask", "Tag", "--subtask", "Announce", "--check", "1"])
+        .output()
+        .unwrap();
+
+    let output = kelvin_in(dir.path()).args(["show", "1"]).output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("1/2 done"));
+    assert!(stdout.contains("1. [x] Tag"));
+    assert!(stdout.contains("2. [ ] Announce"));
+
+    let output = kelvin_in(dir.path()).args(["burn", "1"]).output().unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("1 open subtask"));
+    assert!(read_tasks_json(dir.path()).contains("\"melted\""));
+
+    // --yes answers prompts but does not override the guard, for burn or pop
+    let output = kelvin_in(dir.path()).args(["burn", "1", "--force"]).output().unwrap();
+    assert!(!output.status.success());
+    let output = kelvin_in(dir.path()).args(["pop", "--yes"]).output().unwrap();
+    assert!(!output.status.success());
+    assert!(read_tasks_json(dir.path()).contains("\"melted\""));
+
+    let output = kelvin_in(dir.path()).args(["burn", "1", "--ignore-subtasks"]).output().unwrap();
+    assert!(output.status.success());
+    assert!(read_tasks_json(dir.path()).contains("\"evaporated\""));
+
+    let output = kelvin_in(dir.path()).args(["edit", "1", "--check", "3"]).output().unwrap();
+    assert!(String::from_utf8_lossy(&output.stderr).contains("no subtask 3"));
+}
+
+#[test]
+fn add_due_from_title_strips_the_phrase() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = kelvin_in(dir.path())
+        .args(["add", "Submit report by 2026-03-01", "--due-from-title"])
+        .output()
+        .expect("Failed to execute kelvin add --due-from-title");
+    assert!(output.status.success());
+    kelvin_in(dir.path()).args(["add", "Stand by me", "--due-from-title"]).output().unwrap();
+    kelvin_in(dir.path()).args(["add", "Pay rent by 2026-04-01"]).output().unwrap();
+
+    let tasks: serde_json::Value = serde_json::from_str(&read_tasks_json(dir.path())).unwrap();
+    assert_eq!(tasks[0]["title"], "Submit report");
+    assert_eq!(tasks[0]["due_date"], "2026-03-01");
+    assert_eq!(tasks[1]["title"], "Stand by me");
+    assert!(tasks[1]["due_date"].is_null());
+    // Without the flag the title is kept as typed
+    assert_eq!(tasks[2]["title"], "Pay rent by 2026-04-01");
+    assert!(tasks[2]["due_date"].is_null());
+}
+
+#[test]
+fn states_prints_every_transition() {
+    let output = kelvin_cmd().arg("states").output().expect("Failed to execute kelvin states");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    for action in ["thaw", "warm", "burn", "cool", "freeze"] {
+        assert!(stdout.contains(&format!("--{action}-->")), "missing {action}");
+    }
+    assert!(stdout.lines().any(|l| l.starts_with("Iced | Melting") && l.contains("Melted")));
+}
+
+#[test]
+fn force_and_yes_skip_the_same_prompts() {
+    let dir = tempfile::tempdir().unwrap();
+    for title in ["A", "B", "C"] {
+        kelvin_in(dir.path()).args(["add", title]).output().unwrap();
+    }
+    let kelvin_dir = dir.path().join(".config").join("kelvin");
+    std::fs::write(kelvin_dir.join("config.toml"), "[defaults]\nconfirm_destructive = true\n")
+        .unwrap();
+
+    // Piped stdin answers no
+    kelvin_in(dir.path()).args(["merge", "2", "1"]).output().unwrap();
+    assert!(read_tasks_json(dir.path()).contains("\"B\""));
+
+    let output = kelvin_in(dir.path()).args(["merge", "2", "1", "-f"]).output().unwrap();
+    assert!(output.status.success());
+    assert!(!read_tasks_json(dir.path()).contains("\"B\""));
+
+    let output = kelvin_in(dir.path()).args(["--force", "burn", "--all-melted"]).output().unwrap();
+    assert!(output.status.success());
+    let tasks: serde_json::Value = serde_json::from_str(&read_tasks_json(dir.path())).unwrap();
+    assert!(tasks.as_array().unwrap().iter().all(|t| t["state"] == "evaporated"));
+}
+
+#[test]
+fn defer_moves_due_date_keeping_weekday() {
+    let dir = tempfile::tempdir().unwrap();
+    // 2030-01-01 is a Tuesday
+    kelvin_in(dir.path()).args(["add", "Weekly sync", "--due", "2030-01-01"]).output().unwrap();
+    let due = || {
+        let tasks: serde_json::Value = serde_json::from_str(&read_tasks_json(dir.path())).unwrap();
+        tasks[0]["due_date"].as_str().unwrap().to_string()
+    };
+
+    let output = kelvin_in(dir.path())
+        .args(["defer", "1", "--by", "10d", "--same-weekday"])
+        .output()
+        .expect("Failed to execute kelvin defer");
+    assert!(output.status.success());
+    assert_eq!(due(), "2030-01-15");
+
+    kelvin_in(dir.path()).args(["defer", "1", "--by", "3d"]).output().unwrap();
+    assert_eq!(due(), "2030-01-18");
+
+    // Deferring never brings the due date forward
+    let output = kelvin_in(dir.path()).args(["defer", "1", "--by", "2030-01-02"]).output().unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("only moves it later"));
+    assert_eq!(due(), "2030-01-18");
+
+    kelvin_in(dir.path()).args(["add", "Undated"]).output().unwrap();
+    let output = kelvin_in(dir.path()).args(["defer", "2"]).output().unwrap();
+    assert!(!output.status.success());
+}
+
+#[test]
+fn summary_prints_one_configurable_line() {
+    let dir = tempfile::tempdir().unwrap();
+    kelvin_in(dir.path()).args(["add", "Due now", "--due", "today"]).output().unwrap();
+    kelvin_in(dir.path()).args(["add", "Later", "-d", "2099-01-01"]).output().unwrap();
+    let before = read_tasks_json(dir.path());
+
+    let output = kelvin_in(dir.path()).arg("summary").output().expect("Failed to execute kelvin summary");
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "🔥1 due today · 1 melted · 0 overdue");
+    assert_eq!(read_tasks_json(dir.path()), before);
+
+    let kelvin_dir = dir.path().join(".config").join("kelvin");
+    std::fs::write(kelvin_dir.join("config.toml"), "[defaults]\nsummary_format = \"{iced}❄ {melted}💧\"\n")
+        .unwrap();
+    let output = kelvin_in(dir.path()).arg("summary").output().unwrap();
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "1❄ 1💧");
+}
+
+#[test]
+fn thaw_to_flag_overrides_config() {
+    let dir = tempfile::tempdir().unwrap();
+    let kelvin_dir = dir.path().join(".config").join("kelvin");
+    std::fs::create_dir_all(&kelvin_dir).unwrap();
+    std::fs::write(kelvin_dir.join("config.toml"), "[defaults]\nthaw_to = \"melting\"\n").unwrap();
+    for title in ["First", "Second"] {
+        kelvin_in(dir.path()).args(["add", title, "-d", "2020-01-01"]).output().unwrap();
+    }
+
+    // The flag wins over the config for this run
+    let output = kelvin_in(dir.path()).args(["--thaw-to", "melted", "list"]).output().unwrap();
+    assert!(output.status.success());
+    let tasks: serde_json::Value = serde_json::from_str(&read_tasks_json(dir.path())).unwrap();
+    assert_eq!(tasks[0]["state"], "melted");
+    assert_eq!(tasks[1]["state"], "melted");
+
+    // Only melting and melted are accepted, on the command line and in the config
+    let output = kelvin_in(dir.path()).args(["list", "--thaw-to", "iced"]).output().unwrap();
+    assert!(!output.status.success());
+    std::fs::write(kelvin_dir.join("config.toml"), "[defaults]\nthaw_to = \"evaporated\"\n").unwrap();
+    let output = kelvin_in(dir.path()).arg("list").output().unwrap();
+    assert!(!output.status.success());
+}
+
+#[test]
+fn validate_reports_problems_in_any_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("hand-edited.json");
+    std::fs::write(
+        &file,
+        r#"[{"id": 1, "title": "A", "description": "", "state": "melted",
+            "created_at": "2026-01-01", "depends_on": [7]},
+           {"id": 1, "title": "B", "description": "", "state": "melting",
+            "created_at": "2026-01-01"}]"#,
+    )
+    .unwrap();
+
+    let output = kelvin_in(dir.path()).arg("validate").arg(&file).output().unwrap();
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("#1: depends on missing task #7"));
+    assert!(stdout.contains("#1: ID is used by more than one task"));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("2 problems found"));
+    // The live task list is never created
+    assert!(!dir.path().join(".config").join("kelvin").join("tasks.json").exists());
+
+    std::fs::write(&file, r#"[{"id": 1, "title": "A", "state": "molten"}]"#).unwrap();
+    let output = kelvin_in(dir.path()).arg("validate").arg(&file).output().unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("molten"));
+
+    kelvin_in(dir.path()).args(["add", "Fine"]).output().unwrap();
+    let tasks = dir.path().join(".config").join("kelvin").join("tasks.json");
+    let output = kelvin_in(dir.path()).arg("validate").arg(&tasks).output().unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("is valid (1 task)"));
+}
+
+#[test]
+fn list_overdue_by_filters_by_severity() {
+    let dir = tempfile::tempdir().unwrap();
+    let today = chrono::Local::now().date_naive();
+    for (title, days_ago) in [("Neglected", 10), ("Slipping", 2), ("Due today", 0)] {
+        let due = (today - chrono::Duration::days(days_ago)).to_string();
+        kelvin_in(dir.path()).args(["add", title, "--due", &due]).output().unwrap();
+    }
+
+    let output = kelvin_in(dir.path()).args(["list", "--overdue-by", "1w"]).output().unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Neglected"));
+    assert!(!stdout.contains("Slipping"));
+
+    let output = kelvin_in(dir.path()).args(["list", "--overdue-by", "0d"]).output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Slipping"));
+    assert!(!stdout.contains("Due today"));
+
+    let output = kelvin_in(dir.path()).args(["list", "--overdue-by", "7"]).output().unwrap();
+    assert!(!output.status.success());
+}
+
+#[test]
+fn list_filters_by_tag_and_excluded_tag() {
+    let dir = tempfile::tempdir().unwrap();
+    kelvin_in(dir.path()).args(["add", "Report", "--tag", "work"]).output().unwrap();
+    kelvin_in(dir.path()).args(["add", "Garden", "--tag", "home"]).output().unwrap();
+    kelvin_in(dir.path()).args(["add", "Taxes", "--tag", "home", "--tag", "urgent"]).output().unwrap();
+    kelvin_in(dir.path()).args(["add", "Untagged"]).output().unwrap();
+
+    let output = kelvin_in(dir.path()).args(["list", "--not-tag", "WORK"]).output().unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!stdout.contains("Report"));
+    assert!(stdout.contains("Garden") && stdout.contains("Taxes") && stdout.contains("Untagged"));
+
+    let output = kelvin_in(dir.path())
+        .args(["list", "--tag", "home", "--not-tagged", "urgent"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Garden"));
+    assert!(!stdout.contains("Taxes") && !stdout.contains("Report") && !stdout.contains("Untagged"));
+}
+
+#[test]
+fn edit_signed_date_moves_the_current_one() {
+    let dir = tempfile::tempdir().unwrap();
+    kelvin_in(dir.path()).args(["add", "Report", "--due", "2030-01-10"]).output().unwrap();
+
+    let output = kelvin_in(dir.path()).args(["edit", "1", "--due", "+3d"]).output().unwrap();
+    assert!(output.status.success());
+    let output = kelvin_in(dir.path()).args(["edit", "1", "--due", "-1w"]).output().unwrap();
+    assert!(output.status.success());
+    let tasks: serde_json::Value = serde_json::from_str(&read_tasks_json(dir.path())).unwrap();
+    assert_eq!(tasks[0]["due_date"], "2030-01-06");
+
+    // Nothing to move without a thaw date
+    let output = kelvin_in(dir.path()).args(["edit", "1", "-d", "+3d"]).output().unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("no thaw date"));
+}
+
+#[test]
+fn unburn_only_within_the_grace_period() {
+    let dir = tempfile::tempdir().unwrap();
+    let kelvin_dir = dir.path().join(".config").join("kelvin");
+    std::fs::create_dir_all(&kelvin_dir).unwrap();
+    std::fs::write(kelvin_dir.join("config.toml"), "[defaults]\nburn_grace_days = 2\n").unwrap();
+    kelvin_in(dir.path()).args(["add", "Send invoice"]).output().unwrap();
+    kelvin_in(dir.path()).args(["add", "Old news"]).output().unwrap();
+    kelvin_in(dir.path()).args(["burn", "1"]).output().unwrap();
+    kelvin_in(dir.path()).args(["burn", "2"]).output().unwrap();
+
+    // Hidden like any burned task
+    let output = kelvin_in(dir.path()).arg("list").output().unwrap();
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("Send invoice"));
+
+    // Let the grace period of task 2 run out
+    let json = read_tasks_json(dir.path());
+    let mut tasks: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert!(tasks[0]["burn_pending_until"].is_string());
+    tasks[1]["burn_pending_until"] = "2000-01-01".into();
+    std::fs::write(kelvin_dir.join("tasks.json"), tasks.to_string()).unwrap();
+
+    let output = kelvin_in(dir.path()).args(["unburn", "1"]).output().unwrap();
+    assert!(output.status.success());
+    let output = kelvin_in(dir.path()).args(["unburn", "2"]).output().unwrap();
+    assert!(!output.status.success());
+
+    let tasks: serde_json::Value = serde_json::from_str(&read_tasks_json(dir.path())).unwrap();
+    assert_eq!(tasks[0]["state"], "melted");
+    assert!(tasks[0].get("burn_pending_until").is_none());
+    assert_eq!(tasks[1]["state"], "evaporated");
+    assert!(tasks[1].get("burn_pending_until").is_none());
+}
+
+#[test]
+fn exists_reports_through_the_exit_status() {
+    let dir = tempfile::tempdir().unwrap();
+    kelvin_in(dir.path()).args(["add", "Frozen", "-d", "2099-01-01"]).output().unwrap();
+
+    let output = kelvin_in(dir.path()).args(["exists", "1"]).output().unwrap();
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty() && output.stderr.is_empty());
+
+    let output = kelvin_in(dir.path()).args(["exists", "1", "--state", "iced"]).output().unwrap();
+    assert!(output.status.success());
+    let output = kelvin_in(dir.path()).args(["exists", "1", "--state", "melted"]).output().unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    assert!(output.stdout.is_empty());
+
+    let output = kelvin_in(dir.path()).args(["exists", "2", "--verbose"]).output().unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "Task 2 not found\n");
+}
+
+#[test]
+fn list_header_follows_display_config() {
+    let dir = tempfile::tempdir().unwrap();
+    kelvin_in(dir.path()).args(["add", "Task"]).output().unwrap();
+    let kelvin_dir = dir.path().join(".config").join("kelvin");
+
+    std::fs::write(kelvin_dir.join("config.toml"), "[display]\nrule_char = \"=\"\n").unwrap();
+    let output = kelvin_in(dir.path()).arg("list").output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.lines().nth(1).unwrap().starts_with("====="));
+    assert!(!stdout.contains('─'));
+
+    std::fs::write(kelvin_dir.join("config.toml"), "[display]\nheader_rule = false\n").unwrap();
+    let output = kelvin_in(dir.path()).arg("list").output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.lines().next().unwrap().starts_with("ID"));
+    assert!(stdout.lines().nth(1).unwrap().contains("Task"));
+
+    std::fs::write(kelvin_dir.join("config.toml"), "[display]\nrule_char = \"-=\"\n").unwrap();
+    let output = kelvin_in(dir.path()).arg("list").output().unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("display.rule_char"));
+}
+
+#[test]
+fn pop_burns_tasks_in_priority_order() {
+    let dir = tempfile::tempdir().unwrap();
+    kelvin_in(dir.path()).args(["add", "Someday"]).output().unwrap();
+    kelvin_in(dir.path()).args(["add", "Urgent", "--priority", "high"]).output().unwrap();
+    kelvin_in(dir.path()).args(["add", "Soon", "--due", "2030-01-01"]).output().unwrap();
+
+    // Not confirmed without a terminal
+    let output = kelvin_in(dir.path()).arg("pop").output().unwrap();
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Aborted."));
+
+    for (burned, next) in [("Urgent", "Next: #3 Soon"), ("Soon", "Next: #1 Someday")] {
+        let output = kelvin_in(dir.path()).args(["pop", "--yes"]).output().unwrap();
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("Burned task") && stdout.contains(burned));
+        assert!(stdout.contains(next), "{stdout}");
+    }
+    let output = kelvin_in(dir.path()).args(["pop", "-y"]).output().unwrap();
+    assert!(String::from_utf8_lossy(&output.stdout).ends_with("All done!\n"));
+    let output = kelvin_in(dir.path()).args(["pop", "-y"]).output().unwrap();
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "All done!\n");
+}
+
+#[test]
+fn list_markdown_format_escapes_pipes() {
+    let dir = tempfile::tempdir().unwrap();
+    kelvin_in(dir.path()).args(["add", "Compare A | B"]).output().unwrap();
+    kelvin_in(dir.path()).args(["add", "Later", "-d", "2099-01-01"]).output().unwrap();
+
+    let output = kelvin_in(dir.path())
+        .args(["list", "--format", "markdown", "--fields", "id,title,state"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "| ID | Task | State |\n| --- | --- | --- |\n| 1 | Compare A \\| B | Melted |\n"
+    );
+
+    // Nothing but the table on stdout, even when rows are cut off
+    let output = kelvin_in(dir.path())
+        .args(["list", "--all", "--format", "markdown", "--fields", "id", "--limit", "1"])
+        .output()
+        .unwrap();
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "| ID |\n| --- |\n| 1 |\n");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("... and 1 more"));
+
+    let output = kelvin_in(dir.path()).args(["list", "--format", "markdown", "--json"]).output().unwrap();
+    assert!(!output.status.success());
+}
+
+#[test]
+fn reminders_list_tasks_within_their_lead_time() {
+    let dir = tempfile::tempdir().unwrap();
+    for args in [
+        ["Pay rent", "--due", "2d", "--remind", "3d"].as_slice(),
+        &["Renew passport", "--due", "3w", "--remind", "1w"],
+        &["No reminder", "--due", "1d"],
+    ] {
+        kelvin_in(dir.path()).arg("add").args(args).output().unwrap();
+    }
+
+    let output = kelvin_in(dir.path()).arg("reminders").output().unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Pay rent"));
+    assert!(!stdout.contains("Renew passport") && !stdout.contains("No reminder"));
+
+    kelvin_in(dir.path()).args(["edit", "1", "--remind", ""]).output().unwrap();
+    let output = kelvin_in(dir.path()).arg("reminders").output().unwrap();
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "No reminders.\n");
+
+    let output = kelvin_in(dir.path()).args(["edit", "2", "--remind", "soon"]).output().unwrap();
+    assert!(!output.status.success());
+}
+
+#[test]
+fn random_sort_is_repeatable_with_a_seed() {
+    let dir = tempfile::tempdir().unwrap();
+    for i in 1..=8 {
+        let priority = if i % 2 == 0 { "high" } else { "low" };
+        kelvin_in(dir.path()).args(["add", &format!("Task {i}"), "-p", priority]).output().unwrap();
+    }
+    let ids = |args: &[&str]| -> Vec<u64> {
+        let output = kelvin_in(dir.path()).args(["list", "--json"]).args(args).output().unwrap();
+        assert!(output.status.success());
+        let tasks: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        tasks.as_array().unwrap().iter().map(|t| t["id"].as_u64().unwrap()).collect()
+    };
+
+    let shuffled = ids(&["--sort", "priority,random", "--seed", "7"]);
+    assert_eq!(shuffled, ids(&["--sort", "priority,random", "--seed", "7"]));
+    // Random order within each priority
+    assert!(shuffled[..4].iter().all(|id| id % 2 == 0));
+    let mut sorted = shuffled.clone();
+    sorted.sort();
+    assert_eq!(sorted, (1..=8).collect::<Vec<_>>());
+
+    let pick = |seed: &str| {
+        let output = kelvin_in(dir.path()).args(["random", "--seed", seed]).output().unwrap();
+        String::from_utf8_lossy(&output.stdout).to_string()
+    };
+    assert_eq!(pick("3"), pick("3"));
+    assert!(pick("3").contains("Task "));
+}
+
+#[test]
+fn thaw_all_melts_every_iced_task() {
+    let dir = tempfile::tempdir().unwrap();
+    kelvin_in(dir.path()).args(["add", "Trip photos", "-d", "2099-01-01"]).output().unwrap();
+    kelvin_in(dir.path()).args(["add", "Taxes", "-d", "2099-04-01"]).output().unwrap();
+    kelvin_in(dir.path()).args(["add", "Inbox"]).output().unwrap();
+
+    let output = kelvin_in(dir.path()).args(["thaw", "--all"]).output().unwrap();
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Thaw 2 Iced tasks?"));
+    assert!(read_tasks_json(dir.path()).contains("iced"));
+
+    let output = kelvin_in(dir.path()).args(["thaw", "--all", "--yes"]).output().unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Thawed 2 tasks."));
+    let tasks: serde_json::Value = serde_json::from_str(&read_tasks_json(dir.path())).unwrap();
+    for task in tasks.as_array().unwrap() {
+        assert_eq!(task["state"], "melted");
+        assert!(task["thaw_date"].is_null());
+    }
+
+    let output = kelvin_in(dir.path()).args(["thaw", "--all", "--yes"]).output().unwrap();
+    assert!(String::from_utf8_lossy(&output.stdout).contains("No Iced tasks to thaw."));
+}
+
+#[test]
+fn add_uses_configured_default_due() {
+    let dir = tempfile::tempdir().unwrap();
+    let kelvin_dir = dir.path().join(".config/kelvin");
+    std::fs::create_dir_all(&kelvin_dir).unwrap();
+    std::fs::write(kelvin_dir.join("config.toml"), "[defaults]\ndefault_due = \"1w\"\n").unwrap();
+
+    kelvin_in(dir.path()).args(["add", "Undated"]).output().unwrap();
+    kelvin_in(dir.path()).args(["add", "Dated", "--due", "2026-03-01"]).output().unwrap();
+
+    let tasks: serde_json::Value = serde_json::from_str(&read_tasks_json(dir.path())).unwrap();
+    let week_out = chrono::Local::now().date_naive() + chrono::Days::new(7);
+    assert_eq!(tasks[0]["due_date"], week_out.to_string());
+    assert_eq!(tasks[1]["due_date"], "2026-03-01");
+}
+
+#[test]
+fn list_json_extended_adds_computed_fields() {
+    let dir = tempfile::tempdir().unwrap();
+    kelvin_in(dir.path()).args(["add", "Late", "--due", "2000-01-01"]).output().unwrap();
+    kelvin_in(dir.path()).args(["add", "Next", "--due", "3d", "--after", "1"]).output().unwrap();
+    kelvin_in(dir.path()).args(["add", "Parked", "-d", "1w"]).output().unwrap();
+
+    let output = kelvin_in(dir.path()).args(["list", "--all", "--json-extended"]).output().unwrap();
+    assert!(output.status.success());
+    let tasks: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(tasks[0]["title"], "Late");
+    assert_eq!(tasks[0]["is_overdue"], true);
+    assert_eq!(tasks[0]["age_days"], 0);
+    assert_eq!(tasks[1]["days_until_due"], 3);
+    assert_eq!(tasks[1]["is_overdue"], false);
+    assert_eq!(tasks[1]["is_blocked"], true);
+    assert!(tasks[1]["days_until_thaw"].is_null());
+    assert_eq!(tasks[2]["days_until_thaw"], 7);
+    assert_eq!(tasks[2]["is_blocked"], false);
+
+    // Plain --json stays the stored task
+    let output = kelvin_in(dir.path()).args(["list", "--all", "--json"]).output().unwrap();
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("is_overdue"));
+    let output = kelvin_in(dir.path())
+        .args(["list", "--json", "--json-extended"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+}
+
+#[test]
+fn config_set_writes_and_validates_keys() {
+    let dir = tempfile::tempdir().unwrap();
+    let config_path = dir.path().join(".config/kelvin/config.toml");
+
+    let output = kelvin_in(dir.path())
+        .args(["config", "set", "defaults.thaw_days", "14"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(std::fs::read_to_string(&config_path).unwrap(), "[defaults]\nthaw_days = 14\n");
+
+    for (key, value) in [("defaults.thaw_days", "many"), ("defaults.date_fmt", "%d")] {
+        let output = kelvin_in(dir.path()).args(["config", "set", key, value]).output().unwrap();
+        assert!(!output.status.success());
+    }
+    assert_eq!(std::fs::read_to_string(&config_path).unwrap(), "[defaults]\nthaw_days = 14\n");
+
+    kelvin_in(dir.path()).args(["add", "Later"]).output().unwrap();
+    kelvin_in(dir.path()).args(["freeze", "1"]).output().unwrap();
+    let week_two = chrono::Local::now().date_naive() + chrono::Days::new(14);
+    assert!(read_tasks_json(dir.path()).contains(&week_two.to_string()));
+}
+
+#[test]
+fn list_contains_matches_title_and_description() {
+    let dir = tempfile::tempdir().unwrap();
+    kelvin_in(dir.path()).args(["add", "Fix LOGIN redirect"]).output().unwrap();
+    kelvin_in(dir.path())
+        .args(["add", "Session bug", "--desc", "after login"])
+        .output()
+        .unwrap();
+    kelvin_in(dir.path()).args(["add", "Old login page", "-d", "1w"]).output().unwrap();
+    kelvin_in(dir.path()).args(["add", "Write docs"]).output().unwrap();
+
+    let titles = |args: &[&str]| {
+        let output = kelvin_in(dir.path()).args(args).output().unwrap();
+        let tasks: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        let titles: Vec<String> = tasks
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t["title"].as_str().unwrap().to_string())
+            .collect();
+        titles
+    };
+    assert_eq!(
+        titles(&["list", "--contains", "Login", "--json"]),
+        ["Fix LOGIN redirect", "Session bug"]
+    );
+    assert_eq!(
+        titles(&["list", "--all", "--contains", "login", "--json"]),
+        ["Fix LOGIN redirect", "Session bug", "Old login page"]
+    );
+}
+
+#[test]
+fn edit_move_to_relocates_the_edited_task() {
+    let dir = tempfile::tempdir().unwrap();
+    kelvin_in(dir.path()).args(["add", "Setup"]).output().unwrap();
+    kelvin_in(dir.path()).args(["add", "Expense report", "--after", "1"]).output().unwrap();
+    let kelvin_dir = dir.path().join(".config").join("kelvin");
+    std::fs::write(kelvin_dir.join("config.toml"), "[lists.work]\nprefix = \"W\"\n").unwrap();
+    std::fs::write(
+        kelvin_dir.join("work.json"),
+        r#"[{"id": 3, "title": "Standup", "description": "", "state": "melted",
+            "thaw_date": null, "due_date": null, "created_at": "2026-01-01"}]"#,
+    )
+    .unwrap();
+
+    let output = kelvin_in(dir.path())
+        .args(["edit", "2", "-p", "high", "--move-to", "work"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("priority:"));
+    assert!(stdout.contains("Moved task 2 from 'tasks' to 'work' as task W-4: Expense report"));
+
+    let tasks: serde_json::Value = serde_json::from_str(&read_tasks_json(dir.path())).unwrap();
+    assert_eq!(tasks.as_array().unwrap().len(), 1);
+    let work = std::fs::read_to_string(kelvin_dir.join("work.json")).unwrap();
+    let work: serde_json::Value = serde_json::from_str(&work).unwrap();
+    assert_eq!(work[1]["id"], 4);
+    assert_eq!(work[1]["priority"], "high");
+    assert!(work[1].get("depends_on").is_none_or(|deps| deps.as_array().unwrap().is_empty()));
+
+    let output = kelvin_in(dir.path()).args(["edit", "1", "--move-to", "home"]).output().unwrap();
+    assert!(!output.status.success());
+    assert_eq!(read_tasks_json(dir.path()).matches("\"id\"").count(), 1);
+}
+
+#[test]
+fn list_shows_todays_completions_when_configured() {
+    let dir = tempfile::tempdir().unwrap();
+    let kelvin_dir = dir.path().join(".config/kelvin");
+    std::fs::create_dir_all(&kelvin_dir).unwrap();
+    let today = chrono::Local::now().date_naive();
+    let yesterday = today.pred_opt().unwrap();
+    std::fs::write(
+        kelvin_dir.join("tasks.json"),
+        format!(
+            r#"[{{"id": 1, "title": "Done today", "description": "", "state": "evaporated",
+                "thaw_date": null, "due_date": null, "created_at": "{yesterday}",
+                "completed_at": "{today}"}},
+              {{"id": 2, "title": "Done yesterday", "description": "", "state": "evaporated",
+                "thaw_date": null, "due_date": null, "created_at": "{yesterday}",
+                "completed_at": "{yesterday}"}},
+              {{"id": 3, "title": "Still open", "description": "", "state": "melted",
+                "thaw_date": null, "due_date": null, "created_at": "{yesterday}"}}]"#
+        ),
+    )
+    .unwrap();
+
+    let output = kelvin_in(dir.path()).args(["list"]).output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Still open"));
+    assert!(!stdout.contains("Done today"));
+
+    std::fs::write(kelvin_dir.join("config.toml"), "[defaults]\nshow_today_done = true\n").unwrap();
+    let output = kelvin_in(dir.path()).args(["list"]).output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!stdout.contains("Done yesterday"));
+    assert!(stdout.find("Still open").unwrap() < stdout.find("Done today").unwrap());
+    // Only the default view is extended
+    let output = kelvin_in(dir.path()).args(["list", "--melted"]).output().unwrap();
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("Done today"));
+    let output = kelvin_in(dir.path()).args(["list", "--json"]).output().unwrap();
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("Done today"));
+    // Other views keep the requested order
+    let output = kelvin_in(dir.path())
+        .args(["list", "--all", "--sort", "title", "--no-header", "--fields", "id"])
+        .output()
+        .unwrap();
+    let ids: Vec<String> =
+        String::from_utf8_lossy(&output.stdout).lines().map(|l| l.trim().to_string()).collect();
+    assert_eq!(ids, ["1", "2", "3"]);
+}
+
+#[test]
+fn count_due_today_prints_only_the_number() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = kelvin_in(dir.path()).args(["count-due-today"]).output().unwrap();
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "0\n");
+
+    kelvin_in(dir.path()).args(["add", "Today", "--due", "today"]).output().unwrap();
+    kelvin_in(dir.path()).args(["add", "Late", "--due", "2000-01-01"]).output().unwrap();
+    kelvin_in(dir.path()).args(["add", "Later", "--due", "3d"]).output().unwrap();
+    kelvin_in(dir.path()).args(["add", "Done", "--due", "today"]).output().unwrap();
+    kelvin_in(dir.path()).args(["burn", "4"]).output().unwrap();
+
+    let output = kelvin_in(dir.path()).args(["count-due-today"]).output().unwrap();
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "2\n");
+    assert!(output.stderr.is_empty());
+}
+
+#[test]
+fn titles_with_tabs_are_stored_with_spaces() {
+    let dir = tempfile::tempdir().unwrap();
+    for title in ["Fix\tlogin", "Plain task"] {
+        kelvin_in(dir.path()).args(["add", title, "--due", "2026-03-01"]).output().unwrap();
+    }
+    kelvin_in(dir.path()).args(["edit", "2", "--desc", "one\ttwo\nthree"]).output().unwrap();
+    let tasks: serde_json::Value = serde_json::from_str(&read_tasks_json(dir.path())).unwrap();
+    assert_eq!(tasks[0]["title"], "Fix login");
+    assert_eq!(tasks[1]["description"], "one two\nthree");
+
+    let output = kelvin_in(dir.path()).args(["list"]).output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!stdout.contains('\t'));
+    let offsets: Vec<usize> = stdout.lines().filter_map(|line| line.find("2026-03-01")).collect();
+    assert_eq!(offsets.len(), 2, "{stdout}");
+    assert_eq!(offsets[0], offsets[1], "misaligned:\n{stdout}");
+}
+
+#[test]
+fn freeze_cascade_freezes_active_dependents() {
+    let dir = tempfile::tempdir().unwrap();
+    kelvin_in(dir.path()).args(["add", "Blocker"]).output().unwrap();
+    kelvin_in(dir.path()).args(["add", "Build", "--after", "1"]).output().unwrap();
+    kelvin_in(dir.path()).args(["add", "Ship", "--after", "2"]).output().unwrap();
+    kelvin_in(dir.path()).args(["add", "Unrelated"]).output().unwrap();
+    kelvin_in(dir.path()).args(["add", "Done dependent", "--after", "1"]).output().unwrap();
+    kelvin_in(dir.path()).args(["burn", "5"]).output().unwrap();
+
+    let output = kelvin_in(dir.path())
+        .args(["freeze", "1", "--cascade", "-d", "2099-01-01"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Froze 3 tasks."));
+    let tasks: serde_json::Value = serde_json::from_str(&read_tasks_json(dir.path())).unwrap();
+    let states: Vec<&str> = tasks
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|t| t["state"].as_str().unwrap())
+        .collect();
+    assert_eq!(states, ["iced", "iced", "iced", "melted", "evaporated"]);
+    assert_eq!(tasks[2]["thaw_date"], "2099-01-01");
+
+    let output = kelvin_in(dir.path())
+        .args(["freeze", "--state", "melted", "--cascade", "--yes"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+}
+
+#[test]
+fn list_explain_filter_counts_each_stage() {
+    let dir = tempfile::tempdir().unwrap();
+    kelvin_in(dir.path()).args(["add", "Fix login", "--tag", "work"]).output().unwrap();
+    kelvin_in(dir.path()).args(["add", "Review", "--tag", "work"]).output().unwrap();
+    kelvin_in(dir.path()).args(["add", "Groceries"]).output().unwrap();
+    kelvin_in(dir.path()).args(["add", "Later", "--tag", "work", "-d", "1w"]).output().unwrap();
+
+    let output = kelvin_in(dir.path())
+        .args(["list", "--tag", "Work", "--contains", "login", "--explain-filter", "--json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr),
+        "all: 4 → state: 3 → tag work: 2 → contains login: 1\n"
+    );
+    let tasks: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(tasks.as_array().unwrap().len(), 1);
+}
+
+#[test]
+fn undo_reverts_several_steps() {
+    let dir = tempfile::tempdir().unwrap();
+    kelvin_in(dir.path()).args(["add", "Keep me"]).output().unwrap();
+    kelvin_in(dir.path()).args(["add", "Mistake"]).output().unwrap();
+    kelvin_in(dir.path()).args(["burn", "1"]).output().unwrap();
+
+    // Refused before anything is asked
+    let config = dir.path().join(".config").join("kelvin").join("config.toml");
+    std::fs::write(&config, "[defaults]\nconfirm_destructive = true\n").unwrap();
+    let output = kelvin_in(dir.path()).args(["undo", "5"]).output().unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("only 3 undo steps kept"));
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("Undo the last 5 changes?"));
+    std::fs::remove_file(&config).unwrap();
+
+    let output = kelvin_in(dir.path()).args(["undo", "2"]).output().unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Undid 2 changes:"));
+    assert!(stdout.find("state change #1").unwrap() < stdout.find("+1 task").unwrap());
+
+    let tasks: serde_json::Value = serde_json::from_str(&read_tasks_json(dir.path())).unwrap();
+    assert_eq!(tasks.as_array().unwrap().len(), 1);
+    assert_eq!(tasks[0]["state"], "melted");
+    assert!(!kelvin_in(dir.path()).args(["undo", "0"]).output().unwrap().status.success());
+}
+
+#[test]
+fn delete_removes_the_task_from_storage() {
+    let dir = tempfile::tempdir().unwrap();
+    kelvin_in(dir.path()).args(["add", "Typo task"]).output().unwrap();
+    kelvin_in(dir.path()).args(["add", "Follow-up", "--after", "1"]).output().unwrap();
+
+    // Without --force the prompt is not answered from a pipe, so nothing is deleted
+    let output = kelvin_in(dir.path()).args(["delete", "1"]).output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Delete task 1? [y/N]"));
+    assert!(stdout.contains("Aborted."));
+
+    let output = kelvin_in(dir.path()).args(["delete", "1", "--force"]).output().unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Deleted task 1: Typo task"));
+
+    let output = kelvin_in(dir.path()).args(["list", "--all"]).output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!stdout.contains("Typo task"));
+    assert!(stdout.contains("Follow-up"));
+    let tasks: serde_json::Value = serde_json::from_str(&read_tasks_json(dir.path())).unwrap();
+    assert!(tasks[0].get("depends_on").is_none_or(|deps| deps.as_array().unwrap().is_empty()));
+
+    let output = kelvin_in(dir.path()).args(["delete", "1", "--force"]).output().unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Task 1 not found"));
+}
+
+#[test]
+fn list_notify_requires_watch() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = kelvin_in(dir.path()).args(["list", "--notify"]).output().unwrap();
+    assert!(!output.status.success());
+}
+
+#[cfg(not(feature = "desktop-notify"))]
+#[test]
+fn desktop_notify_requires_feature() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = kelvin_in(dir.path())
+        .args(["list", "--watch", "--notify", "desktop"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("desktop-notify"));
+}
+
+#[cfg(not(feature = "sqlite"))]
+#[test]
+fn sqlite_backend_requires_feature() {
+    let dir = tempfile::tempdir().unwrap();
+    let kelvin_dir = dir.path().join(".config").join("kelvin");
+    std::fs::create_dir_all(&kelvin_dir).unwrap();
+    std::fs::write(kelvin_dir.join("config.toml"), "[storage]\nbackend = \"sqlite\"\n").unwrap();
+
+    let output = kelvin_in(dir.path()).args(["list"]).output().unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("`sqlite` feature"));
+}
+
+#[test]
+fn reads_and_no_op_commands_do_not_write() {
+    let dir = tempfile::tempdir().unwrap();
+    let tasks_path = dir.path().join(".config").join("kelvin").join("tasks.json");
+
+    kelvin_in(dir.path()).args(["add", "Steady"]).output().unwrap();
+    let output = kelvin_in(dir.path())
+        .args(["freeze", "1", "-d", "2099-01-01"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let modified = std::fs::metadata(&tasks_path).unwrap().modified().unwrap();
+
+    for args in [
+        &["list"][..],
+        &["list", "--all"],
+        &["show", "1"],
+        &["graph"],
+        &["export"],
+        &["freeze", "1", "-d", "2099-01-01"],
+        &["edit", "1", "-t", "Steady"],
+        &["warm", "9"],
+    ] {
+        kelvin_in(dir.path()).args(args).output().unwrap();
+        assert_eq!(
+            std::fs::metadata(&tasks_path).unwrap().modified().unwrap(),
+            modified,
+            "`kelvin {}` rewrote tasks.json",
+            args.join(" ")
+        );
+    }
+}